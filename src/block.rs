@@ -0,0 +1,201 @@
+//! Leader/Anchor blocks: the phases a run of cycles is split into and the
+//! programming each phase uses.
+
+use std::fmt;
+use strum_macros::EnumString;
+
+use crate::lifts::{PrimaryVariant, SeventhWeek, SupplementalTemplate, Week, WorkoutError};
+
+/// Phase of a Leader/Anchor block
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumString)]
+pub enum BlockPhase {
+    /// Higher-volume cycles that build up to the 7th week
+    #[strum(serialize = "leader")]
+    Leader,
+    /// Intensity cycles done after the Leader cycles' 7th week
+    #[strum(serialize = "anchor")]
+    Anchor,
+}
+
+impl fmt::Display for BlockPhase {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s: &str = match self {
+            BlockPhase::Leader => "Leader",
+            BlockPhase::Anchor => "Anchor",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+/// Position of a session's week within a Leader/Anchor block
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlockPosition {
+    /// Leader or Anchor
+    pub phase: BlockPhase,
+    /// 1-based cycle within the phase; ignored for the 7th week
+    pub cycle: u8,
+    /// Week 1-3 of the cycle, or `Week7` for the 7th week after the phase
+    pub week: Week,
+}
+
+impl fmt::Display for BlockPosition {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.week {
+            Week::Week7(protocol) => write!(f, "{} 7th week ({})", self.phase, protocol),
+            _ => write!(
+                f,
+                "{} cycle {}, week {}",
+                self.phase,
+                self.cycle,
+                self.week.number()
+            ),
+        }
+    }
+}
+
+/// Programming used for every cycle of a phase
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PhaseTemplate {
+    /// Number of three week cycles in the phase
+    pub cycles: u8,
+    /// How the primary lift's working sets are done
+    pub variant: PrimaryVariant,
+    /// Supplemental work done after the primary lift
+    pub supplemental: SupplementalTemplate,
+    /// Protocol for the 7th week after the phase's cycles
+    pub seventh_week: SeventhWeek,
+}
+
+/// A Leader/Anchor block: Leader cycles, a 7th week, Anchor cycles, and another 7th week.
+/// Cycles within a block are three weeks long; the 7th week takes the place of week 4.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Block {
+    /// Programming for the Leader cycles, done first
+    pub leader: PhaseTemplate,
+    /// Programming for the Anchor cycles, done after the Leader's 7th week
+    pub anchor: PhaseTemplate,
+}
+
+impl Default for Block {
+    /// Two Leader cycles of 5s PRO + BBB and a deload 7th week, then one Anchor
+    /// cycle of PR sets + FSL and a TM test 7th week.
+    fn default() -> Self {
+        Block {
+            leader: PhaseTemplate {
+                cycles: 2,
+                variant: PrimaryVariant::FivesPro,
+                supplemental: SupplementalTemplate::BoringButBig,
+                seventh_week: SeventhWeek::Deload,
+            },
+            anchor: PhaseTemplate {
+                cycles: 1,
+                variant: PrimaryVariant::Standard,
+                supplemental: SupplementalTemplate::FirstSetLast,
+                seventh_week: SeventhWeek::TmTest,
+            },
+        }
+    }
+}
+
+impl Block {
+    /// Programming for `phase`
+    pub fn phase(&self, phase: &BlockPhase) -> &PhaseTemplate {
+        match phase {
+            BlockPhase::Leader => &self.leader,
+            BlockPhase::Anchor => &self.anchor,
+        }
+    }
+
+    /// Programming for the session at `position`, after checking that the position exists in the block.
+    pub fn template_at(&self, position: &BlockPosition) -> Result<&PhaseTemplate, WorkoutError> {
+        let template = self.phase(&position.phase);
+
+        if position.week == Week::Week4 {
+            return Err(WorkoutError::Config(format!(
+                "{} cycles have no week 4; use week 7 for the 7th week",
+                position.phase
+            )));
+        }
+
+        if !matches!(position.week, Week::Week7(_))
+            && (position.cycle == 0 || position.cycle > template.cycles)
+        {
+            return Err(WorkoutError::Config(format!(
+                "{} cycle {} is out of range; the block has {} {} cycle(s)",
+                position.phase, position.cycle, template.cycles, position.phase
+            )));
+        }
+
+        Ok(template)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_block_is_leader_five_pro_bbb_and_anchor_fsl() {
+        let block = Block::default();
+        let leader = BlockPosition {
+            phase: BlockPhase::Leader,
+            cycle: 2,
+            week: Week::Week3,
+        };
+        let anchor = BlockPosition {
+            phase: BlockPhase::Anchor,
+            cycle: 1,
+            week: Week::Week1,
+        };
+        let leader_template = block.template_at(&leader).unwrap();
+        assert_eq!(leader_template.variant, PrimaryVariant::FivesPro);
+        assert_eq!(
+            leader_template.supplemental,
+            SupplementalTemplate::BoringButBig
+        );
+        let anchor_template = block.template_at(&anchor).unwrap();
+        assert_eq!(anchor_template.variant, PrimaryVariant::Standard);
+        assert_eq!(
+            anchor_template.supplemental,
+            SupplementalTemplate::FirstSetLast
+        );
+        assert_eq!(leader.to_string(), "Leader cycle 2, week 3");
+    }
+
+    #[test]
+    fn template_at_rejects_positions_outside_block() {
+        let block = Block::default();
+        let err = block
+            .template_at(&BlockPosition {
+                phase: BlockPhase::Anchor,
+                cycle: 2,
+                week: Week::Week1,
+            })
+            .unwrap_err();
+        assert!(err.to_string().contains("Anchor cycle 2 is out of range"));
+
+        let err = block
+            .template_at(&BlockPosition {
+                phase: BlockPhase::Leader,
+                cycle: 1,
+                week: Week::Week4,
+            })
+            .unwrap_err();
+        assert!(err.to_string().contains("no week 4"));
+
+        let seventh = BlockPosition {
+            phase: BlockPhase::Leader,
+            cycle: 0,
+            week: Week::Week7(SeventhWeek::Deload),
+        };
+        assert!(block.template_at(&seventh).is_ok());
+        assert_eq!(seventh.to_string(), "Leader 7th week (deload)");
+    }
+
+    #[test]
+    fn default_block_deloads_after_leader_and_tests_after_anchor() {
+        let block = Block::default();
+        assert_eq!(block.leader.seventh_week, SeventhWeek::Deload);
+        assert_eq!(block.anchor.seventh_week, SeventhWeek::TmTest);
+    }
+}
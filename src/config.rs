@@ -0,0 +1,1942 @@
+//! Parsing of the TOML config file: training maxes and the optional tables
+//! that configure programming, units, rounding and plates. A [`Config`] is
+//! parsed once and each of its methods reads one table; `source` names the
+//! file in error messages.
+
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::block::{Block, BlockPhase, BlockPosition, PhaseTemplate};
+use crate::e1rm::E1rmFormula;
+use crate::lifts::{
+    BbbPercent, Lift, PrimaryVariant, SetsReps, SeventhWeek, Supplemental, SupplementalTemplate,
+    Week, WidowmakerLift, WorkoutError,
+};
+use crate::plates::PlateInventory;
+use crate::rounding::{clean as clean_weight, Rounding, RoundingMode, RoundingRules};
+use crate::schedule::Schedule;
+use crate::scheme::{Scheme, SetScheme, WeekScheme, BUILT_IN_SCHEMES};
+use crate::session::{Session, SessionOptions};
+use crate::units::{DisplayUnit, Unit, Units};
+use crate::warm_up::{WarmUp, WarmUpRules, DEFAULT_EMPTY_BAR_REPS};
+
+#[derive(Deserialize)]
+struct TrainingMaxConfig {
+    default: HashMap<String, f32>,
+    #[serde(default)]
+    supplemental: SupplementalConfig,
+    #[serde(default)]
+    block: BlockConfig,
+    #[serde(default)]
+    scheme: SchemeSelectionConfig,
+    #[serde(default)]
+    schemes: HashMap<String, SchemeConfig>,
+    #[serde(default)]
+    progression: ProgressionConfig,
+    #[serde(default)]
+    e1rm: E1rmConfig,
+    #[serde(default)]
+    plates: PlatesConfig,
+    #[serde(default)]
+    rounding: RoundingConfig,
+    #[serde(default)]
+    units: UnitsConfig,
+    #[serde(default)]
+    schedule: ScheduleConfig,
+    #[serde(default)]
+    warm_up: WarmUpConfig,
+}
+
+/// Optional `[warm_up]` table of the config file
+#[derive(Deserialize, Default)]
+struct WarmUpConfig {
+    /// Ramp as percent x reps, e.g. `["40x5", "50x5", "60x3"]`
+    sets: Option<Vec<String>>,
+    empty_bar: Option<bool>,
+    empty_bar_reps: Option<i8>,
+    /// Skip warm-up sets within this percent of training max of the first working set
+    min_gap_percent: Option<f32>,
+    /// Per-lift overrides, e.g. `[warm_up.deadlift]`
+    #[serde(flatten)]
+    lifts: HashMap<String, WarmUpConfig>,
+}
+
+/// Optional `[schedule]` table of the config file
+#[derive(Deserialize, Default)]
+struct ScheduleConfig {
+    days: Option<u8>,
+}
+
+/// Optional `[units]` table of the config file
+#[derive(Deserialize, Default)]
+struct UnitsConfig {
+    unit: Option<String>,
+    display: Option<String>,
+}
+
+/// Optional `[rounding]` table of the config file
+#[derive(Deserialize, Default)]
+struct RoundingConfig {
+    increment: Option<f32>,
+    mode: Option<String>,
+    /// Per-lift overrides, e.g. `[rounding.overhead_press]`
+    #[serde(flatten)]
+    lifts: HashMap<String, RoundingConfig>,
+}
+
+/// Optional `[plates]` table of the config file
+#[derive(Deserialize, Default)]
+struct PlatesConfig {
+    bar: Option<f32>,
+    /// Pairs of plates available by plate weight, e.g. `45 = 8` or `"2.5" = 2`
+    pairs: Option<HashMap<String, u16>>,
+}
+
+/// Optional `[e1rm]` table of the config file
+#[derive(Deserialize, Default)]
+struct E1rmConfig {
+    formula: Option<String>,
+}
+
+/// Optional `[progression]` table of the config file
+#[derive(Deserialize, Default)]
+struct ProgressionConfig {
+    upper_body: Option<f32>,
+    lower_body: Option<f32>,
+    /// Per-lift increments, e.g. `squat = 15`
+    #[serde(flatten)]
+    lifts: HashMap<String, f32>,
+}
+
+/// Optional `[scheme]` table of the config file
+#[derive(Deserialize, Default)]
+struct SchemeSelectionConfig {
+    name: Option<String>,
+}
+
+/// A `[schemes.NAME]` table of the config file. Weeks that aren't given are
+/// taken from the `base` built-in scheme.
+#[derive(Deserialize, Default)]
+struct SchemeConfig {
+    base: Option<String>,
+    week1: Option<WeekSchemeConfig>,
+    week2: Option<WeekSchemeConfig>,
+    week3: Option<WeekSchemeConfig>,
+    week4: Option<WeekSchemeConfig>,
+    week7_deload: Option<WeekSchemeConfig>,
+    week7_tm_test: Option<WeekSchemeConfig>,
+}
+
+/// Sets for one week of a `[schemes.NAME]` table, e.g. `working = ["65x5", "75x5", "85x5+"]`
+#[derive(Deserialize, Default)]
+struct WeekSchemeConfig {
+    warm_up: Option<Vec<String>>,
+    working: Option<Vec<String>>,
+    assistance: Option<Vec<String>>,
+    explosive_assistance: Option<Vec<String>>,
+}
+
+/// Optional `[block]` table of the config file
+#[derive(Deserialize, Default)]
+struct BlockConfig {
+    #[serde(default)]
+    leader: PhaseConfig,
+    #[serde(default)]
+    anchor: PhaseConfig,
+}
+
+/// `[block.leader]` or `[block.anchor]` table of the config file
+#[derive(Deserialize, Default)]
+struct PhaseConfig {
+    cycles: Option<u8>,
+    variant: Option<String>,
+    supplemental: Option<String>,
+    seventh_week: Option<String>,
+}
+
+/// Optional `[supplemental]` table of the config file
+#[derive(Deserialize, Default)]
+struct SupplementalConfig {
+    template: Option<String>,
+    bbb_percent: Option<String>,
+    scheme: Option<String>,
+    widowmaker_lift: Option<String>,
+    widowmaker_percent: Option<u8>,
+    widowmaker_reps: Option<i8>,
+    /// Per-primary-lift overrides, e.g. `[supplemental.squat]`
+    #[serde(flatten)]
+    lifts: HashMap<String, SupplementalConfig>,
+}
+
+const DEFAULT_WIDOWMAKER_PERCENT: u8 = 60;
+const DEFAULT_WIDOWMAKER_REPS: i8 = 20;
+
+/// Supplemental template and options, from either the CLI or the config file
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct SupplementalChoice {
+    /// Which supplemental work to do; none if unset
+    pub template: Option<SupplementalTemplate>,
+    /// Percent of training max for Boring But Big; 50 if unset
+    pub bbb_percent: Option<BbbPercent>,
+    /// Sets and reps for First Set Last or Second Set Last; 5x5 if unset
+    pub scheme: Option<SetsReps>,
+    /// Lift the Widowmaker set is done with; the primary lift if unset
+    pub widowmaker_lift: Option<WidowmakerLift>,
+    /// Percent of training max for the Widowmaker set; 60 if unset
+    pub widowmaker_percent: Option<u8>,
+    /// Reps of the Widowmaker set; 20 if unset
+    pub widowmaker_reps: Option<i8>,
+}
+
+impl SupplementalChoice {
+    /// Fills options not set in `self` from `fallback`.
+    pub fn or(self, fallback: SupplementalChoice) -> SupplementalChoice {
+        SupplementalChoice {
+            template: self.template.or(fallback.template),
+            bbb_percent: self.bbb_percent.or(fallback.bbb_percent),
+            scheme: self.scheme.or(fallback.scheme),
+            widowmaker_lift: self.widowmaker_lift.or(fallback.widowmaker_lift),
+            widowmaker_percent: self.widowmaker_percent.or(fallback.widowmaker_percent),
+            widowmaker_reps: self.widowmaker_reps.or(fallback.widowmaker_reps),
+        }
+    }
+
+    /// Combines the template and its options into the work to generate.
+    pub fn resolve(&self) -> Option<Supplemental> {
+        let scheme = self.scheme.unwrap_or(SetsReps { sets: 5, reps: 5 });
+        match self.template.unwrap_or(SupplementalTemplate::None) {
+            SupplementalTemplate::None => None,
+            SupplementalTemplate::BoringButBig => Some(Supplemental::BoringButBig(
+                self.bbb_percent.unwrap_or(BbbPercent::Fifty),
+            )),
+            SupplementalTemplate::FirstSetLast => Some(Supplemental::FirstSetLast(scheme)),
+            SupplementalTemplate::SecondSetLast => Some(Supplemental::SecondSetLast(scheme)),
+            SupplementalTemplate::Widowmaker => Some(Supplemental::Widowmaker {
+                lift: self.widowmaker_lift.unwrap_or(WidowmakerLift::Primary),
+                percent: self
+                    .widowmaker_percent
+                    .unwrap_or(DEFAULT_WIDOWMAKER_PERCENT),
+                reps: self.widowmaker_reps.unwrap_or(DEFAULT_WIDOWMAKER_REPS),
+            }),
+        }
+    }
+}
+
+const DEFAULT_UPPER_BODY_INCREMENT: f32 = 5.0;
+const DEFAULT_LOWER_BODY_INCREMENT: f32 = 10.0;
+const DEFAULT_UPPER_BODY_INCREMENT_KG: f32 = 2.5;
+const DEFAULT_LOWER_BODY_INCREMENT_KG: f32 = 5.0;
+
+/// Training max increments applied by the `progress` command
+#[derive(Debug, PartialEq)]
+pub struct Progression {
+    /// Increment for upper body lifts without their own
+    pub upper_body: f32,
+    /// Increment for lower body lifts without their own
+    pub lower_body: f32,
+    /// Per-lift increments, overriding `upper_body` and `lower_body`
+    pub lifts: HashMap<Lift, f32>,
+}
+
+impl Progression {
+    /// Amount `lift`'s training max goes up by for the next cycle.
+    pub fn increment(&self, lift: &Lift) -> f32 {
+        match self.lifts.get(lift) {
+            Some(increment) => *increment,
+            None if lift.is_upper_body() => self.upper_body,
+            None => self.lower_body,
+        }
+    }
+}
+
+/// A training max before and after `progress`
+#[derive(Debug, PartialEq)]
+pub struct TrainingMaxChange {
+    /// Lift whose training max changed
+    pub lift: Lift,
+    /// Training max before `progress`
+    pub before: f32,
+    /// Training max after `progress`
+    pub after: f32,
+}
+
+/// Parses a primary lift name or alias, e.g. `squat`, `bp` or `ohp`; assistance
+/// lifts are rejected.
+pub fn parse_primary_lift(src: &str) -> Result<Lift, String> {
+    let lift = Lift::from_str(src).map_err(|_| {
+        format!(
+            "Invalid primary lift '{src}'. Valid values are: squat/s/bench-press/bench_press/b/bp/deadlift/d/dl/overhead-press/o/ohp/p."
+        )
+    })?;
+
+    if !Lift::PRIMARY_LIFTS.contains(&lift) {
+        return Err(format!(
+            "Invalid primary lift '{src}'. Valid values are: squat/s/bench-press/bench_press/b/bp/deadlift/d/dl/overhead-press/o/ohp/p."
+        ));
+    }
+
+    Ok(lift)
+}
+
+/// Parses a week number: 1-4, or 7 for a deload 7th week.
+pub fn parse_week(src: &str) -> Result<Week, String> {
+    match src {
+        "1" => Ok(Week::Week1),
+        "2" => Ok(Week::Week2),
+        "3" => Ok(Week::Week3),
+        "4" => Ok(Week::Week4),
+        "7" => Ok(Week::Week7(SeventhWeek::Deload)),
+        _ => Err("week must be 1, 2, 3, 4, or 7".to_owned()),
+    }
+}
+
+/// Parses `leader` or `anchor`.
+pub fn parse_block_phase(src: &str) -> Result<BlockPhase, String> {
+    BlockPhase::from_str(src)
+        .map_err(|_| format!("Invalid block phase '{src}'. Valid values are: leader/anchor."))
+}
+
+/// Parses a 7th week protocol: `deload` or `tm-test`.
+pub fn parse_seventh_week(src: &str) -> Result<SeventhWeek, String> {
+    SeventhWeek::from_str(src).map_err(|_| {
+        format!("Invalid 7th week protocol '{src}'. Valid values are: deload/tm-test/test.")
+    })
+}
+
+/// Parses a primary lift variant: `standard`, `5s-pro` or `pyramid`.
+pub fn parse_primary_variant(src: &str) -> Result<PrimaryVariant, String> {
+    PrimaryVariant::from_str(src).map_err(|_| {
+        format!("Invalid primary variant '{src}'. Valid values are: standard/531/5s-pro/fives-pro/pyramid/pr-set-down.")
+    })
+}
+
+/// Parses a supplemental template name, e.g. `bbb`, `fsl` or `widowmaker`.
+pub fn parse_supplemental_template(src: &str) -> Result<SupplementalTemplate, String> {
+    SupplementalTemplate::from_str(src).map_err(|_| {
+        format!("Invalid supplemental template '{src}'. Valid values are: none/bbb/boring-but-big/fsl/first-set-last/ssl/second-set-last/widowmaker/wm.")
+    })
+}
+
+/// Parses a Boring But Big percent: 50, 60, 70, `ascending` or `descending`.
+pub fn parse_bbb_percent(src: &str) -> Result<BbbPercent, String> {
+    BbbPercent::from_str(src).map_err(|_| {
+        format!("Invalid BBB percent '{src}'. Valid values are: 50/60/70/ascending/descending.")
+    })
+}
+
+/// Parses supplemental sets and reps written as `SETSxREPS`, e.g. `5x5`.
+pub fn parse_sets_reps(src: &str) -> Result<SetsReps, String> {
+    SetsReps::from_str(src)
+}
+
+/// Parses the lift a Widowmaker set is done with: `primary` or `assistance`.
+pub fn parse_widowmaker_lift(src: &str) -> Result<WidowmakerLift, String> {
+    WidowmakerLift::from_str(src).map_err(|_| {
+        format!(
+            "Invalid widowmaker lift '{src}'. Valid values are: primary/assistance/big-assistance."
+        )
+    })
+}
+
+/// Parses an e1RM formula name, e.g. `wendler` or `epley`.
+pub fn parse_e1rm_formula(src: &str) -> Result<E1rmFormula, String> {
+    E1rmFormula::from_str(src).map_err(|_| {
+        format!("Invalid e1RM formula '{src}'. Valid values are: wendler/epley/brzycki/lombardi.")
+    })
+}
+
+/// Parses a rounding mode: `nearest`, `down` or `up`.
+pub fn parse_rounding_mode(src: &str) -> Result<RoundingMode, String> {
+    RoundingMode::from_str(src)
+        .map_err(|_| format!("Invalid rounding mode '{src}'. Valid values are: nearest/down/up."))
+}
+
+/// Parses `lb` or `kg`.
+pub fn parse_unit(src: &str) -> Result<Unit, String> {
+    Unit::from_str(src).map_err(|_| format!("Invalid unit '{src}'. Valid values are: lb/kg."))
+}
+
+/// Parses the unit weights are printed in: `lb`, `kg` or `both`.
+pub fn parse_display_unit(src: &str) -> Result<DisplayUnit, String> {
+    DisplayUnit::from_str(src)
+        .map_err(|_| format!("Invalid display unit '{src}'. Valid values are: lb/kg/both."))
+}
+
+/// Parses a number of training days per week: 2, 3 or 4.
+pub fn parse_schedule(src: &str) -> Result<Schedule, String> {
+    Schedule::from_str(src)
+        .map_err(|_| format!("Invalid training days per week '{src}'. Valid values are: 2/3/4."))
+}
+
+/// Checks that the big assistance lift done with `primary_lift`, if any, has a
+/// training max.
+pub fn validate_required_assistance_training_max(
+    primary_lift: &Lift,
+    training_maxes: &HashMap<Lift, f32>,
+) -> Result<(), WorkoutError> {
+    let required_lift = match primary_lift.big_assistance() {
+        Some(lift) => lift,
+        None => return Ok(()),
+    };
+
+    training_maxes
+        .get(&required_lift)
+        .map(|_| ())
+        .ok_or(WorkoutError::MissingTrainingMax {
+            lift: required_lift,
+        })?;
+
+    Ok(())
+}
+
+/// Reads a config file's contents, naming the file if it can't be read.
+pub fn read_config_file(path: &Path) -> Result<String, WorkoutError> {
+    let source = path.to_string_lossy().into_owned();
+    std::fs::read_to_string(path)
+        .map_err(|err| WorkoutError::Config(format!("Unable to read {}: {}", source, err)))
+}
+
+/// Reads and parses the training maxes of the config file at `path`.
+pub fn load_training_maxes_from_file(path: &Path) -> Result<HashMap<Lift, f32>, WorkoutError> {
+    let contents = read_config_file(path)?;
+    parse_training_maxes_from_str(&contents, &path.to_string_lossy())
+}
+
+/// A config file parsed once, to read each of its tables from as needed
+pub struct Config {
+    cfg: TrainingMaxConfig,
+    /// Names the file in error messages
+    source: String,
+}
+
+impl Config {
+    /// Parses the contents of a config file; `source` names it in error messages.
+    pub fn parse(contents: &str, source: &str) -> Result<Config, WorkoutError> {
+        let cfg = toml::from_str(contents).map_err(|err| {
+            WorkoutError::Config(format!("Unable to parse {} as TOML: {}", source, err))
+        })?;
+        Ok(Config {
+            cfg,
+            source: source.to_owned(),
+        })
+    }
+
+    /// Training maxes from the `[default]` table, which must have every primary lift.
+    pub fn training_maxes(&self) -> Result<HashMap<Lift, f32>, WorkoutError> {
+        let source = self.source.as_str();
+        let cfg = &self.cfg;
+
+        let mut ret = HashMap::new();
+        for (lift_name, raw_weight) in cfg.default.iter() {
+            let lift = Lift::from_str(lift_name).map_err(|_| {
+                WorkoutError::Config(format!(
+                    "Unknown lift '{}' in training max file {}",
+                    lift_name, source
+                ))
+            })?;
+
+            if !raw_weight.is_finite() || *raw_weight <= 0.0 {
+                return Err(WorkoutError::Config(format!(
+                    "Training max for '{}' in {} must be a positive number, got {}",
+                    lift_name, source, raw_weight
+                )));
+            }
+
+            ret.insert(lift, *raw_weight);
+        }
+
+        let missing_primary_lifts: Vec<String> = Lift::PRIMARY_LIFTS
+            .iter()
+            .filter(|lift| !ret.contains_key(lift))
+            .map(|lift| lift.to_string())
+            .collect();
+
+        if !missing_primary_lifts.is_empty() {
+            return Err(WorkoutError::Config(format!(
+                "Missing required primary lift training max(es) in {}: {}",
+                source,
+                missing_primary_lifts.join(", ")
+            )));
+        }
+
+        Ok(ret)
+    }
+
+    /// Parses the optional `[supplemental]` table for a primary lift. Options in a
+    /// per-lift table such as `[supplemental.squat]` take precedence over the
+    /// top-level ones.
+    pub fn supplemental(&self, primary_lift: &Lift) -> Result<SupplementalChoice, WorkoutError> {
+        let source = self.source.as_str();
+        let cfg = &self.cfg.supplemental;
+
+        let mut lift_choice = SupplementalChoice::default();
+        for (lift_name, lift_cfg) in cfg.lifts.iter() {
+            let lift = Lift::from_str(lift_name)
+                .ok()
+                .filter(|lift| Lift::PRIMARY_LIFTS.contains(lift))
+                .ok_or_else(|| {
+                    WorkoutError::Config(format!(
+                        "Unknown primary lift '{}' in [supplemental] table of {}",
+                        lift_name, source
+                    ))
+                })?;
+            if !lift_cfg.lifts.is_empty() {
+                return Err(WorkoutError::Config(format!(
+                    "Nested tables are not supported in [supplemental.{}] of {}",
+                    lift_name, source
+                )));
+            }
+            if lift == *primary_lift {
+                lift_choice = supplemental_choice_from_config(lift_cfg, source)?;
+            }
+        }
+
+        Ok(lift_choice.or(supplemental_choice_from_config(cfg, source)?))
+    }
+
+    /// Parses the optional `[block]` table, filling unset options from the default block.
+    pub fn block(&self) -> Result<Block, WorkoutError> {
+        let source = self.source.as_str();
+        let cfg = &self.cfg.block;
+        let default = Block::default();
+
+        let phase_template = |phase: &str, cfg: &PhaseConfig, default: &PhaseTemplate| {
+            let in_source = |err: String| {
+                WorkoutError::Config(format!("{} (in [block.{}] of {})", err, phase, source))
+            };
+            let cycles = cfg.cycles.unwrap_or(default.cycles);
+            if cycles == 0 {
+                return Err(in_source("cycles must be at least 1".to_owned()));
+            }
+            Ok(PhaseTemplate {
+                cycles,
+                variant: cfg
+                    .variant
+                    .as_deref()
+                    .map(|raw| parse_primary_variant(raw).map_err(in_source))
+                    .transpose()?
+                    .unwrap_or(default.variant),
+                supplemental: cfg
+                    .supplemental
+                    .as_deref()
+                    .map(|raw| parse_supplemental_template(raw).map_err(in_source))
+                    .transpose()?
+                    .unwrap_or(default.supplemental),
+                seventh_week: cfg
+                    .seventh_week
+                    .as_deref()
+                    .map(|raw| parse_seventh_week(raw).map_err(in_source))
+                    .transpose()?
+                    .unwrap_or(default.seventh_week),
+            })
+        };
+
+        Ok(Block {
+            leader: phase_template("leader", &cfg.leader, &default.leader)?,
+            anchor: phase_template("anchor", &cfg.anchor, &default.anchor)?,
+        })
+    }
+
+    /// Parses the scheme named by `name` (or the `[scheme]` table), which is either
+    /// built in or defined in a `[schemes.NAME]` table.
+    pub fn scheme(&self, name: Option<&str>) -> Result<Scheme, WorkoutError> {
+        let source = self.source.as_str();
+        let cfg = &self.cfg;
+        let name = name
+            .or(cfg.scheme.name.as_deref())
+            .unwrap_or(BUILT_IN_SCHEMES[0]);
+
+        let scheme_cfg = match cfg.schemes.get(name) {
+            Some(scheme_cfg) => scheme_cfg,
+            None => {
+                return Scheme::built_in(name).ok_or_else(|| {
+                    WorkoutError::Config(format!(
+                        "Unknown scheme '{}'. Define it under [schemes.{}] in {} or use a built-in scheme: {}",
+                        name,
+                        name,
+                        source,
+                        BUILT_IN_SCHEMES.join(", ")
+                    ))
+                })
+            }
+        };
+
+        let base = scheme_cfg.base.as_deref().unwrap_or(BUILT_IN_SCHEMES[0]);
+        let mut scheme = Scheme::built_in(base).ok_or_else(|| {
+            WorkoutError::Config(format!(
+                "Unknown base scheme '{}' in [schemes.{}] of {}. Valid values are: {}",
+                base,
+                name,
+                source,
+                BUILT_IN_SCHEMES.join(", ")
+            ))
+        })?;
+
+        let weeks = [
+            ("week1", Week::Week1, &scheme_cfg.week1),
+            ("week2", Week::Week2, &scheme_cfg.week2),
+            ("week3", Week::Week3, &scheme_cfg.week3),
+            ("week4", Week::Week4, &scheme_cfg.week4),
+            (
+                "week7_deload",
+                Week::Week7(SeventhWeek::Deload),
+                &scheme_cfg.week7_deload,
+            ),
+            (
+                "week7_tm_test",
+                Week::Week7(SeventhWeek::TmTest),
+                &scheme_cfg.week7_tm_test,
+            ),
+        ];
+        for (key, week, week_cfg) in weeks.iter() {
+            let week_cfg = match week_cfg {
+                Some(week_cfg) => week_cfg,
+                None => continue,
+            };
+            let in_source = |err: String| {
+                WorkoutError::Config(format!(
+                    "{} (in [schemes.{}.{}] of {})",
+                    err, name, key, source
+                ))
+            };
+            let parse_sets = |raw: &Option<Vec<String>>, default: &Vec<SetScheme>| match raw {
+                Some(raw) => raw
+                    .iter()
+                    .map(|set| SetScheme::from_str(set).map_err(in_source))
+                    .collect::<Result<Vec<_>, _>>(),
+                None => Ok(default.clone()),
+            };
+
+            let base_week = scheme
+                .week(week)
+                .ok_or_else(|| in_source("the base scheme has no sets for this week".to_owned()))?;
+            let week_scheme = WeekScheme {
+                warm_up: match week_cfg.warm_up {
+                    Some(_) => Some(parse_sets(&week_cfg.warm_up, &Vec::new())?),
+                    None => base_week.warm_up.clone(),
+                },
+                working: parse_sets(&week_cfg.working, &base_week.working)?,
+                assistance: parse_sets(&week_cfg.assistance, &base_week.assistance)?,
+                explosive_assistance: parse_sets(
+                    &week_cfg.explosive_assistance,
+                    &base_week.explosive_assistance,
+                )?,
+            };
+            if week_scheme.working.is_empty() {
+                return Err(in_source("working sets must not be empty".to_owned()));
+            }
+            scheme.set_week(*week, week_scheme);
+        }
+
+        Ok(scheme)
+    }
+
+    /// Parses the optional `[progression]` table. The `upper_body` and
+    /// `lower_body` increments given (e.g. on the command line) take precedence
+    /// over the table's, but not over per-lift keys. Without either, increments
+    /// default to 5/10 lb or 2.5/5 kg.
+    pub fn progression(
+        &self,
+        upper_body_increment: Option<f32>,
+        lower_body_increment: Option<f32>,
+    ) -> Result<Progression, WorkoutError> {
+        let source = self.source.as_str();
+        let cfg = &self.cfg.progression;
+        let (upper_body, lower_body) = match self.units()?.unit {
+            Unit::Pound => (DEFAULT_UPPER_BODY_INCREMENT, DEFAULT_LOWER_BODY_INCREMENT),
+            Unit::Kilogram => (
+                DEFAULT_UPPER_BODY_INCREMENT_KG,
+                DEFAULT_LOWER_BODY_INCREMENT_KG,
+            ),
+        };
+
+        let mut lifts = HashMap::new();
+        for (lift_name, increment) in cfg.lifts.iter() {
+            let lift = Lift::from_str(lift_name).map_err(|_| {
+                WorkoutError::Config(format!(
+                    "Unknown lift '{}' in [progression] table of {}",
+                    lift_name, source
+                ))
+            })?;
+            lifts.insert(lift, *increment);
+        }
+
+        Ok(Progression {
+            upper_body: upper_body_increment
+                .or(cfg.upper_body)
+                .unwrap_or(upper_body),
+            lower_body: lower_body_increment
+                .or(cfg.lower_body)
+                .unwrap_or(lower_body),
+            lifts,
+        })
+    }
+
+    /// Parses the formula in the optional `[e1rm]` table.
+    pub fn e1rm_formula(&self) -> Result<Option<E1rmFormula>, WorkoutError> {
+        let source = self.source.as_str();
+        let cfg = &self.cfg.e1rm;
+        cfg.formula
+            .as_deref()
+            .map(|raw| {
+                parse_e1rm_formula(raw)
+                    .map_err(|err| WorkoutError::Config(format!("{} (in {})", err, source)))
+            })
+            .transpose()
+    }
+
+    /// Parses the optional `[schedule]` table.
+    pub fn schedule(&self) -> Result<Option<Schedule>, WorkoutError> {
+        let source = self.source.as_str();
+        let cfg = &self.cfg.schedule;
+        cfg.days
+            .map(|days| {
+                parse_schedule(&days.to_string()).map_err(|err| {
+                    WorkoutError::Config(format!("{} (in [schedule] of {})", err, source))
+                })
+            })
+            .transpose()
+    }
+
+    /// Parses the optional `[units]` table. Weights are in pounds unless it says otherwise.
+    pub fn units(&self) -> Result<Units, WorkoutError> {
+        let source = self.source.as_str();
+        let cfg = &self.cfg.units;
+        let in_source =
+            |err: String| WorkoutError::Config(format!("{} (in [units] of {})", err, source));
+        Ok(Units {
+            unit: cfg
+                .unit
+                .as_deref()
+                .map(|raw| parse_unit(raw).map_err(in_source))
+                .transpose()?
+                .unwrap_or_default(),
+            display: cfg
+                .display
+                .as_deref()
+                .map(|raw| parse_display_unit(raw).map_err(in_source))
+                .transpose()?,
+        })
+    }
+
+    /// Parses the optional `[plates]` table, using the default bar and plates for
+    /// `unit` for anything not given.
+    pub fn plates(&self, unit: Unit) -> Result<PlateInventory, WorkoutError> {
+        let source = self.source.as_str();
+        let cfg = &self.cfg.plates;
+        let mut default = PlateInventory::for_unit(unit);
+        let in_source =
+            |err: String| WorkoutError::Config(format!("{} (in [plates] of {})", err, source));
+
+        let bar = cfg.bar.unwrap_or(default.bar);
+        if bar < 0.0 {
+            return Err(in_source(format!("bar must not be negative, got {}", bar)));
+        }
+
+        let pairs = match &cfg.pairs {
+            Some(pairs) => pairs,
+            None => {
+                default.bar = bar;
+                return Ok(default);
+            }
+        };
+        let mut inventory = vec![];
+        for (raw_plate, count) in pairs.iter() {
+            let plate = raw_plate
+                .parse::<f32>()
+                .ok()
+                .filter(|plate| *plate > 0.0)
+                .ok_or_else(|| in_source(format!("Invalid plate weight '{}'", raw_plate)))?;
+            inventory.push((plate, *count));
+        }
+        Ok(PlateInventory::new(bar, inventory))
+    }
+
+    /// Parses the optional `[rounding]` table. Per-lift tables such as
+    /// `[rounding.overhead_press]` fill unset options from the top-level ones, and
+    /// the top-level ones default to the rounding for `unit`.
+    pub fn rounding(&self, unit: Unit) -> Result<RoundingRules, WorkoutError> {
+        let source = self.source.as_str();
+        let cfg = &self.cfg.rounding;
+
+        let rounding_from_config = |table: &str, cfg: &RoundingConfig, default: &Rounding| {
+            let in_source = |err: String| {
+                WorkoutError::Config(format!("{} (in [{}] of {})", err, table, source))
+            };
+            let increment = match cfg.increment {
+                Some(increment) if !increment.is_finite() || increment <= 0.0 => {
+                    return Err(in_source(format!(
+                        "increment must be a positive number, got {}",
+                        increment
+                    )))
+                }
+                Some(increment) => increment,
+                None => default.increment,
+            };
+            Ok(Rounding {
+                increment,
+                mode: cfg
+                    .mode
+                    .as_deref()
+                    .map(|raw| parse_rounding_mode(raw).map_err(in_source))
+                    .transpose()?
+                    .unwrap_or(default.mode),
+            })
+        };
+
+        let default = rounding_from_config("rounding", cfg, &Rounding::for_unit(unit))?;
+        let mut lifts = HashMap::new();
+        for (lift_name, lift_cfg) in cfg.lifts.iter() {
+            let lift = Lift::from_str(lift_name).map_err(|_| {
+                WorkoutError::Config(format!(
+                    "Unknown lift '{}' in [rounding] table of {}",
+                    lift_name, source
+                ))
+            })?;
+            if !lift_cfg.lifts.is_empty() {
+                return Err(WorkoutError::Config(format!(
+                    "Nested tables are not supported in [rounding.{}] of {}",
+                    lift_name, source
+                )));
+            }
+            let table = format!("rounding.{}", lift_name);
+            lifts.insert(lift, rounding_from_config(&table, lift_cfg, &default)?);
+        }
+
+        Ok(RoundingRules { default, lifts })
+    }
+
+    /// Parses the optional `[warm_up]` table. Per-lift tables such as
+    /// `[warm_up.deadlift]` fill unset options from the top-level ones. The empty
+    /// bar weighs `bar`.
+    pub fn warm_up(&self, bar: f32) -> Result<WarmUpRules, WorkoutError> {
+        let source = self.source.as_str();
+        let cfg = &self.cfg.warm_up;
+
+        let warm_up_from_config = |table: &str, cfg: &WarmUpConfig, default: &WarmUp| {
+            let in_source = |err: String| {
+                WorkoutError::Config(format!("{} (in [{}] of {})", err, table, source))
+            };
+            let sets = match &cfg.sets {
+                Some(sets) => sets
+                    .iter()
+                    .map(|set| SetScheme::from_str(set).map_err(in_source))
+                    .collect::<Result<Vec<_>, _>>()?,
+                None => default.sets.clone(),
+            };
+            if let Some(set) = sets.iter().find(|set| set.amrap) {
+                return Err(in_source(format!(
+                    "warm-up set '{}' must not be AMRAP",
+                    set
+                )));
+            }
+            if let Some(reps) = cfg.empty_bar_reps.filter(|reps| *reps <= 0) {
+                return Err(in_source(format!(
+                    "empty_bar_reps must be positive, got {}",
+                    reps
+                )));
+            }
+            let min_gap = match cfg.min_gap_percent {
+                Some(percent) if !(0.0..=100.0).contains(&percent) => {
+                    return Err(in_source(format!(
+                        "min_gap_percent must be between 0 and 100, got {}",
+                        percent
+                    )))
+                }
+                Some(percent) => percent / 100.0,
+                None => default.min_gap,
+            };
+            let empty_bar_reps = match cfg.empty_bar {
+                Some(false) => None,
+                Some(true) => Some(
+                    cfg.empty_bar_reps
+                        .or(default.empty_bar_reps)
+                        .unwrap_or(DEFAULT_EMPTY_BAR_REPS),
+                ),
+                None => default
+                    .empty_bar_reps
+                    .map(|reps| cfg.empty_bar_reps.unwrap_or(reps)),
+            };
+            Ok(WarmUp {
+                sets,
+                empty_bar_reps,
+                bar,
+                min_gap,
+            })
+        };
+
+        let default = warm_up_from_config("warm_up", cfg, &WarmUp::default())?;
+        let mut lifts = HashMap::new();
+        for (lift_name, lift_cfg) in cfg.lifts.iter() {
+            let lift = Lift::from_str(lift_name)
+                .ok()
+                .filter(|lift| Lift::PRIMARY_LIFTS.contains(lift))
+                .ok_or_else(|| {
+                    WorkoutError::Config(format!(
+                        "Unknown primary lift '{}' in [warm_up] table of {}",
+                        lift_name, source
+                    ))
+                })?;
+            if !lift_cfg.lifts.is_empty() {
+                return Err(WorkoutError::Config(format!(
+                    "Nested tables are not supported in [warm_up.{}] of {}",
+                    lift_name, source
+                )));
+            }
+            let table = format!("warm_up.{}", lift_name);
+            lifts.insert(lift, warm_up_from_config(&table, lift_cfg, &default)?);
+        }
+
+        Ok(WarmUpRules { default, lifts })
+    }
+
+    /// Programming for a session of `primary_lift` in `week`. Options given
+    /// take precedence over the Leader/Anchor phase's template, which takes
+    /// precedence over the config file's tables.
+    pub fn session(
+        &self,
+        primary_lift: Lift,
+        week: Week,
+        options: &SessionOptions,
+    ) -> Result<Session, WorkoutError> {
+        let unit = self.units()?.unit;
+        let training_maxes = self.training_maxes()?;
+        validate_required_assistance_training_max(&primary_lift, &training_maxes)?;
+
+        let mut position = options.block.map(|phase| BlockPosition {
+            phase,
+            cycle: options.cycle,
+            week,
+        });
+        let phase_template = match position {
+            Some(position) => Some(*self.block()?.template_at(&position)?),
+            None => None,
+        };
+
+        let week = match week {
+            Week::Week7(_) => Week::Week7(
+                options
+                    .seventh_week
+                    .or(phase_template.map(|template| template.seventh_week))
+                    .unwrap_or(SeventhWeek::Deload),
+            ),
+            week => week,
+        };
+        if let Some(position) = position.as_mut() {
+            position.week = week;
+        }
+
+        let variant = options
+            .variant
+            .or(phase_template.map(|template| template.variant))
+            .unwrap_or(PrimaryVariant::Standard);
+        let block_choice = SupplementalChoice {
+            template: phase_template.map(|template| template.supplemental),
+            ..Default::default()
+        };
+        let supplemental = options
+            .supplemental
+            .or(block_choice)
+            .or(self.supplemental(&primary_lift)?)
+            .resolve();
+
+        let mut scheme = self.scheme(options.scheme.as_deref())?;
+        if let Some(increment) = options.joker_increment {
+            scheme.set_joker_increment(increment);
+        }
+        let bar = self.plates(unit)?.bar;
+        scheme.set_warm_up(self.warm_up(bar)?);
+        let rounding = self.rounding(unit)?;
+
+        Ok(Session {
+            primary_lift,
+            week,
+            position,
+            phase_template,
+            variant,
+            jokers: options.jokers,
+            supplemental,
+            scheme,
+            rounding,
+            training_maxes,
+        })
+    }
+}
+
+/// Parses `contents` and reads [`Config::training_maxes`] from it.
+pub fn parse_training_maxes_from_str(
+    contents: &str,
+    source: &str,
+) -> Result<HashMap<Lift, f32>, WorkoutError> {
+    Config::parse(contents, source)?.training_maxes()
+}
+
+fn supplemental_choice_from_config(
+    cfg: &SupplementalConfig,
+    source: &str,
+) -> Result<SupplementalChoice, WorkoutError> {
+    let in_source = |err: String| WorkoutError::Config(format!("{} (in {})", err, source));
+
+    if let Some(percent) = cfg.widowmaker_percent {
+        if percent == 0 || percent > 100 {
+            return Err(in_source(format!(
+                "widowmaker_percent must be between 1 and 100, got {}",
+                percent
+            )));
+        }
+    }
+    if let Some(reps) = cfg.widowmaker_reps {
+        if reps <= 0 {
+            return Err(in_source(format!(
+                "widowmaker_reps must be positive, got {}",
+                reps
+            )));
+        }
+    }
+
+    Ok(SupplementalChoice {
+        template: cfg
+            .template
+            .as_deref()
+            .map(|raw| parse_supplemental_template(raw).map_err(in_source))
+            .transpose()?,
+        bbb_percent: cfg
+            .bbb_percent
+            .as_deref()
+            .map(|raw| parse_bbb_percent(raw).map_err(in_source))
+            .transpose()?,
+        scheme: cfg
+            .scheme
+            .as_deref()
+            .map(|raw| parse_sets_reps(raw).map_err(in_source))
+            .transpose()?,
+        widowmaker_lift: cfg
+            .widowmaker_lift
+            .as_deref()
+            .map(|raw| parse_widowmaker_lift(raw).map_err(in_source))
+            .transpose()?,
+        widowmaker_percent: cfg.widowmaker_percent,
+        widowmaker_reps: cfg.widowmaker_reps,
+    })
+}
+
+/// Bumps every training max in the `[default]` table by its increment. Returns
+/// the new file contents, with comments and layout kept as they were, and the
+/// changes in file order.
+pub fn progress_training_maxes(
+    contents: &str,
+    source: &str,
+    progression: &Progression,
+) -> Result<(String, Vec<TrainingMaxChange>), WorkoutError> {
+    let training_maxes = parse_training_maxes_from_str(contents, source)?;
+    let mut doc = contents.parse::<toml_edit::DocumentMut>().map_err(|err| {
+        WorkoutError::Config(format!("Unable to parse {} as TOML: {}", source, err))
+    })?;
+    let table = doc["default"]
+        .as_table_like_mut()
+        .ok_or_else(|| WorkoutError::Config(format!("Missing [default] table in {}", source)))?;
+
+    let mut changes = Vec::new();
+    for (lift_name, item) in table.iter_mut() {
+        // unknown lifts were rejected when parsing the training maxes
+        let lift = Lift::from_str(&lift_name).unwrap();
+        let before = training_maxes[&lift];
+        let after = clean_weight(before + progression.increment(&lift));
+
+        // whole numbers stay integers in the file; anything else is written as
+        // it prints, e.g. 102.5 rather than its f64 expansion
+        let old = item.as_value().unwrap();
+        let mut new = if after.fract() == 0.0 {
+            toml_edit::Value::from(after as i64)
+        } else {
+            toml_edit::Value::from(after.to_string().parse::<f64>().unwrap())
+        };
+        *new.decor_mut() = old.decor().clone();
+        *item = toml_edit::Item::Value(new);
+
+        changes.push(TrainingMaxChange {
+            lift,
+            before,
+            after,
+        });
+    }
+
+    let new_contents = doc.to_string();
+    parse_training_maxes_from_str(&new_contents, source)?;
+    Ok((new_contents, changes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lifts::generate_primary_sets;
+    use std::fs;
+    use std::path::PathBuf;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    fn write_temp_config(contents: &str) -> PathBuf {
+        let mut path = std::env::temp_dir();
+        let unique = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("clock moved backwards")
+            .as_nanos();
+        path.push(format!("five-three-one-config-{}.toml", unique));
+        fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn parse_week_supports_only_supported_values() {
+        assert_eq!(parse_week("1").unwrap(), Week::Week1);
+        assert_eq!(parse_week("2").unwrap(), Week::Week2);
+        assert_eq!(parse_week("3").unwrap(), Week::Week3);
+        assert_eq!(parse_week("4").unwrap(), Week::Week4);
+        assert_eq!(parse_week("7").unwrap(), Week::Week7(SeventhWeek::Deload));
+        assert_eq!(
+            parse_week("0"),
+            Err("week must be 1, 2, 3, 4, or 7".to_owned())
+        );
+        assert_eq!(
+            parse_week("5"),
+            Err("week must be 1, 2, 3, 4, or 7".to_owned())
+        );
+    }
+
+    #[test]
+    fn parse_primary_lift_accepts_supported_aliases() {
+        assert_eq!(parse_primary_lift("squat").unwrap(), Lift::Squat);
+        assert_eq!(parse_primary_lift("s").unwrap(), Lift::Squat);
+        assert_eq!(parse_primary_lift("bench-press").unwrap(), Lift::BenchPress);
+        assert_eq!(parse_primary_lift("bench_press").unwrap(), Lift::BenchPress);
+        assert_eq!(parse_primary_lift("b").unwrap(), Lift::BenchPress);
+        assert_eq!(parse_primary_lift("deadlift").unwrap(), Lift::Deadlift);
+        assert_eq!(parse_primary_lift("dl").unwrap(), Lift::Deadlift);
+        assert_eq!(
+            parse_primary_lift("overhead-press").unwrap(),
+            Lift::OverheadPress
+        );
+        assert_eq!(parse_primary_lift("p").unwrap(), Lift::OverheadPress);
+    }
+
+    #[test]
+    fn parse_primary_lift_rejects_assistance_lifts() {
+        assert!(parse_primary_lift("front_squat").is_err());
+        assert!(parse_primary_lift("power_clean").is_err());
+        assert!(parse_primary_lift("close_grip_bench_press").is_err());
+    }
+
+    #[test]
+    fn parse_training_maxes_from_toml_requires_primary_lifts() {
+        let config = "[default]
+squat = 325
+bench_press = 235
+deadlift = 365";
+        let err = parse_training_maxes_from_str(config, "test-training_max.toml").unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Missing required primary lift training max"));
+        assert!(err.to_string().contains("overhead press"));
+    }
+
+    #[test]
+    fn validate_required_assistance_training_max_for_primary_lift() {
+        let config = "[default]
+squat = 325
+bench_press = 235
+deadlift = 365
+overhead_press = 170";
+        let training_maxes = parse_training_maxes_from_str(config, "training_max.toml").unwrap();
+        let err =
+            validate_required_assistance_training_max(&Lift::Squat, &training_maxes).unwrap_err();
+        assert_eq!(
+            err,
+            WorkoutError::MissingTrainingMax {
+                lift: Lift::PowerClean
+            }
+        );
+    }
+
+    #[test]
+    fn parse_training_maxes_rejects_invalid_values_and_unknown_lifts() {
+        let missing_primary = "[default]
+squat = 325
+bench_press = 235
+deadlift = 365
+overhead_press = 170
+fakelift = 200";
+        assert!(parse_training_maxes_from_str(missing_primary, "training_max.toml").is_err());
+
+        let negative = "[default]
+squat = -325
+bench_press = 235
+deadlift = 365
+overhead_press = 170";
+        assert!(parse_training_maxes_from_str(negative, "training_max.toml").is_err());
+    }
+
+    #[test]
+    fn parse_training_maxes_from_file_uses_provided_path() {
+        let config = "[default]
+squat = 325
+bench_press = 235
+deadlift = 365
+overhead_press = 170";
+        let path = write_temp_config(config);
+        let result = parse_training_maxes_from_str(config, "training_max.toml").unwrap();
+
+        let from_disk = load_training_maxes_from_file(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(result, from_disk);
+        assert_eq!(from_disk.get(&Lift::Squat), Some(&325.0));
+    }
+
+    #[test]
+    fn supplemental_config_rejects_unknown_template() {
+        let config = "[default]
+squat = 325
+bench_press = 235
+deadlift = 365
+overhead_press = 170
+
+[supplemental]
+template = \"bbbb\"";
+        let err = Config::parse(config, "training_max.toml")
+            .unwrap()
+            .supplemental(&Lift::Squat)
+            .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Invalid supplemental template 'bbbb'"));
+    }
+
+    #[test]
+    fn supplemental_config_per_lift_tables_override_top_level() {
+        let config = "[default]
+squat = 325
+bench_press = 235
+deadlift = 365
+overhead_press = 170
+
+[supplemental]
+template = \"fsl\"
+
+[supplemental.squat]
+template = \"widowmaker\"
+widowmaker_percent = 55
+
+[supplemental.deadlift]
+template = \"widowmaker\"
+widowmaker_lift = \"assistance\"
+widowmaker_reps = 15";
+        let squat = Config::parse(config, "training_max.toml")
+            .unwrap()
+            .supplemental(&Lift::Squat)
+            .unwrap();
+        let deadlift = Config::parse(config, "training_max.toml")
+            .unwrap()
+            .supplemental(&Lift::Deadlift)
+            .unwrap();
+        let bench = Config::parse(config, "training_max.toml")
+            .unwrap()
+            .supplemental(&Lift::BenchPress)
+            .unwrap();
+        assert_eq!(
+            squat.resolve(),
+            Some(Supplemental::Widowmaker {
+                lift: WidowmakerLift::Primary,
+                percent: 55,
+                reps: 20
+            })
+        );
+        assert_eq!(
+            deadlift.resolve(),
+            Some(Supplemental::Widowmaker {
+                lift: WidowmakerLift::BigAssistance,
+                percent: DEFAULT_WIDOWMAKER_PERCENT,
+                reps: 15
+            })
+        );
+        assert_eq!(
+            bench.resolve(),
+            Some(Supplemental::FirstSetLast(SetsReps { sets: 5, reps: 5 }))
+        );
+    }
+
+    #[test]
+    fn supplemental_config_rejects_unknown_lift_tables_and_bad_percent() {
+        let base = "[default]
+squat = 325
+bench_press = 235
+deadlift = 365
+overhead_press = 170
+";
+        let unknown_lift = format!(
+            "{}\n[supplemental.front_squat]\ntemplate = \"widowmaker\"",
+            base
+        );
+        let err = Config::parse(&unknown_lift, "training_max.toml")
+            .unwrap()
+            .supplemental(&Lift::Squat)
+            .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Unknown primary lift 'front_squat'"));
+
+        let bad_percent = format!("{}\n[supplemental]\nwidowmaker_percent = 150", base);
+        let err = Config::parse(&bad_percent, "training_max.toml")
+            .unwrap()
+            .supplemental(&Lift::Squat)
+            .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("widowmaker_percent must be between 1 and 100"));
+    }
+
+    #[test]
+    fn block_config_overrides_default_phase_templates() {
+        let config = "[default]
+squat = 325
+bench_press = 235
+deadlift = 365
+overhead_press = 170
+
+[block.leader]
+cycles = 3
+supplemental = \"ssl\"
+
+[block.anchor]
+variant = \"pyramid\"
+seventh_week = \"deload\"";
+        let block = Config::parse(config, "training_max.toml")
+            .unwrap()
+            .block()
+            .unwrap();
+        assert_eq!(
+            block.leader,
+            PhaseTemplate {
+                cycles: 3,
+                variant: PrimaryVariant::FivesPro,
+                supplemental: SupplementalTemplate::SecondSetLast,
+                seventh_week: SeventhWeek::Deload,
+            }
+        );
+        assert_eq!(block.anchor.variant, PrimaryVariant::Pyramid);
+        assert_eq!(block.anchor.seventh_week, SeventhWeek::Deload);
+        assert_eq!(
+            block.anchor.supplemental,
+            SupplementalTemplate::FirstSetLast
+        );
+
+        let no_block = "[default]
+squat = 325
+bench_press = 235
+deadlift = 365
+overhead_press = 170";
+        assert_eq!(
+            Config::parse(no_block, "training_max.toml")
+                .unwrap()
+                .block()
+                .unwrap(),
+            Block::default()
+        );
+    }
+
+    #[test]
+    fn block_config_rejects_zero_cycles() {
+        let config = "[default]
+squat = 325
+bench_press = 235
+deadlift = 365
+overhead_press = 170
+
+[block.anchor]
+cycles = 0";
+        let err = Config::parse(config, "training_max.toml")
+            .unwrap()
+            .block()
+            .unwrap_err();
+        assert!(err.to_string().contains("cycles must be at least 1"));
+    }
+
+    #[test]
+    fn session_options_override_block_which_overrides_config() {
+        let config = Config::parse(
+            "[default]
+squat = 325
+bench_press = 235
+deadlift = 365
+overhead_press = 170
+power_clean = 185
+
+[supplemental]
+template = \"fsl\"",
+            "training_max.toml",
+        )
+        .unwrap();
+
+        let session = config
+            .session(Lift::Squat, Week::Week1, &SessionOptions::default())
+            .unwrap();
+        assert_eq!(session.variant, PrimaryVariant::Standard);
+        assert!(matches!(
+            session.supplemental,
+            Some(Supplemental::FirstSetLast(_))
+        ));
+
+        let leader = SessionOptions {
+            block: Some(BlockPhase::Leader),
+            ..Default::default()
+        };
+        let session = config.session(Lift::Squat, Week::Week1, &leader).unwrap();
+        assert_eq!(session.variant, PrimaryVariant::FivesPro);
+        assert!(matches!(
+            session.supplemental,
+            Some(Supplemental::BoringButBig(_))
+        ));
+        let session = config
+            .session(Lift::Squat, Week::Week7(SeventhWeek::Deload), &leader)
+            .unwrap();
+        assert_eq!(session.week, Week::Week7(SeventhWeek::Deload));
+
+        let overridden = SessionOptions {
+            seventh_week: Some(SeventhWeek::TmTest),
+            variant: Some(PrimaryVariant::Pyramid),
+            supplemental: SupplementalChoice {
+                template: Some(SupplementalTemplate::SecondSetLast),
+                ..Default::default()
+            },
+            ..leader
+        };
+        let session = config
+            .session(Lift::Squat, Week::Week7(SeventhWeek::Deload), &overridden)
+            .unwrap();
+        assert_eq!(session.week, Week::Week7(SeventhWeek::TmTest));
+        assert_eq!(session.variant, PrimaryVariant::Pyramid);
+        assert!(matches!(
+            session.supplemental,
+            Some(Supplemental::SecondSetLast(_))
+        ));
+    }
+
+    #[test]
+    fn scheme_config_selects_built_in_and_custom_schemes() {
+        let base = "[default]
+squat = 325
+bench_press = 235
+deadlift = 365
+overhead_press = 170
+";
+        assert_eq!(
+            Config::parse(base, "training_max.toml")
+                .unwrap()
+                .scheme(None)
+                .unwrap(),
+            Scheme::default()
+        );
+        assert_eq!(
+            Config::parse(base, "training_max.toml")
+                .unwrap()
+                .scheme(Some("351"))
+                .unwrap(),
+            Scheme::three_five_one()
+        );
+
+        let custom = format!(
+            "{}
+[scheme]
+name = \"heavy\"
+
+[schemes.heavy.week1]
+working = [\"70x5\", \"80x5\", \"90x5+\"]",
+            base
+        );
+        let scheme = Config::parse(&custom, "training_max.toml")
+            .unwrap()
+            .scheme(None)
+            .unwrap();
+        let default = Scheme::default();
+        assert_eq!(
+            scheme.week(&Week::Week1).unwrap().working,
+            vec![
+                SetScheme::new(0.7, 5, false),
+                SetScheme::new(0.8, 5, false),
+                SetScheme::new(0.9, 5, true)
+            ]
+        );
+        assert_eq!(
+            scheme.week(&Week::Week1).unwrap().warm_up,
+            default.week(&Week::Week1).unwrap().warm_up
+        );
+        assert_eq!(scheme.week(&Week::Week2), default.week(&Week::Week2));
+    }
+
+    #[test]
+    fn scheme_config_rejects_unknown_schemes_and_bad_sets() {
+        let base = "[default]
+squat = 325
+bench_press = 235
+deadlift = 365
+overhead_press = 170
+";
+        let err = Config::parse(base, "training_max.toml")
+            .unwrap()
+            .scheme(Some("hepburn"))
+            .unwrap_err();
+        assert!(err.to_string().contains("Unknown scheme 'hepburn'"));
+
+        let bad_set = format!("{}\n[schemes.bad.week2]\nworking = [\"90\"]", base);
+        let err = Config::parse(&bad_set, "training_max.toml")
+            .unwrap()
+            .scheme(Some("bad"))
+            .unwrap_err();
+        assert!(err.to_string().contains("Invalid set '90'"));
+        assert!(err.to_string().contains("[schemes.bad.week2]"));
+
+        let empty = format!("{}\n[schemes.empty.week3]\nworking = []", base);
+        let err = Config::parse(&empty, "training_max.toml")
+            .unwrap()
+            .scheme(Some("empty"))
+            .unwrap_err();
+        assert!(err.to_string().contains("working sets must not be empty"));
+    }
+
+    #[test]
+    fn progress_bumps_upper_and_lower_body_and_keeps_comments() {
+        let config = "# my maxes
+[default]
+squat = 325 # felt heavy
+bench_press = 235
+deadlift = 365
+overhead_press = 170
+
+[progression]
+front_squat = 5
+";
+        let progression = Config::parse(config, "training_max.toml")
+            .unwrap()
+            .progression(None, None)
+            .unwrap();
+        assert_eq!(
+            progression.increment(&Lift::OverheadPress),
+            DEFAULT_UPPER_BODY_INCREMENT
+        );
+        assert_eq!(
+            progression.increment(&Lift::Deadlift),
+            DEFAULT_LOWER_BODY_INCREMENT
+        );
+        assert_eq!(progression.increment(&Lift::FrontSquat), 5.0);
+
+        let (contents, changes) =
+            progress_training_maxes(config, "training_max.toml", &progression).unwrap();
+        assert!(contents
+            .starts_with("# my maxes\n[default]\nsquat = 335 # felt heavy\nbench_press = 240\n"));
+        assert!(contents.ends_with("[progression]\nfront_squat = 5\n"));
+        assert_eq!(
+            changes[0],
+            TrainingMaxChange {
+                lift: Lift::Squat,
+                before: 325.0,
+                after: 335.0
+            }
+        );
+        assert_eq!(changes.len(), 4);
+    }
+
+    #[test]
+    fn plates_config_overrides_bar_and_inventory() {
+        let config = "[default]
+squat = 325
+bench_press = 235
+deadlift = 365
+overhead_press = 170
+";
+        assert_eq!(
+            Config::parse(config, "training_max.toml")
+                .unwrap()
+                .plates(Unit::Pound)
+                .unwrap(),
+            PlateInventory::default()
+        );
+        assert_eq!(
+            Config::parse(config, "training_max.toml")
+                .unwrap()
+                .plates(Unit::Kilogram)
+                .unwrap(),
+            PlateInventory::for_unit(Unit::Kilogram)
+        );
+
+        let custom = format!(
+            "{}\n[plates]\nbar = 35\n\n[plates.pairs]\n45 = 2\n\"1.25\" = 1",
+            config
+        );
+        let inventory = Config::parse(&custom, "training_max.toml")
+            .unwrap()
+            .plates(Unit::Kilogram)
+            .unwrap();
+        assert_eq!(
+            inventory,
+            PlateInventory::new(35.0, vec![(45.0, 2), (1.25, 1)])
+        );
+
+        let bad = format!("{}\n[plates.pairs]\nheavy = 2", config);
+        let err = Config::parse(&bad, "training_max.toml")
+            .unwrap()
+            .plates(Unit::Pound)
+            .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Invalid plate weight 'heavy' (in [plates] of training_max.toml)"));
+    }
+
+    #[test]
+    fn schedule_config_sets_days() {
+        let config = "[default]
+squat = 325
+bench_press = 235
+deadlift = 365
+overhead_press = 170
+";
+        assert_eq!(
+            Config::parse(config, "training_max.toml")
+                .unwrap()
+                .schedule()
+                .unwrap(),
+            None
+        );
+
+        let two_day = format!("{}\n[schedule]\ndays = 2", config);
+        assert_eq!(
+            Config::parse(&two_day, "training_max.toml")
+                .unwrap()
+                .schedule()
+                .unwrap(),
+            Some(Schedule::TwoDay)
+        );
+
+        let bad = format!("{}\n[schedule]\ndays = 5", config);
+        let err = Config::parse(&bad, "training_max.toml")
+            .unwrap()
+            .schedule()
+            .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Invalid training days per week '5'. Valid values are: 2/3/4."));
+    }
+
+    #[test]
+    fn e1rm_config_sets_formula() {
+        let config = "[default]
+squat = 325
+bench_press = 235
+deadlift = 365
+overhead_press = 170
+";
+        assert_eq!(
+            Config::parse(config, "training_max.toml")
+                .unwrap()
+                .e1rm_formula()
+                .unwrap(),
+            None
+        );
+
+        let epley = format!("{}\n[e1rm]\nformula = \"epley\"", config);
+        assert_eq!(
+            Config::parse(&epley, "training_max.toml")
+                .unwrap()
+                .e1rm_formula()
+                .unwrap(),
+            Some(E1rmFormula::Epley)
+        );
+
+        let bad = format!("{}\n[e1rm]\nformula = \"guess\"", config);
+        let err = Config::parse(&bad, "training_max.toml")
+            .unwrap()
+            .e1rm_formula()
+            .unwrap_err();
+        assert!(err.to_string().contains("Invalid e1RM formula 'guess'"));
+    }
+
+    #[test]
+    fn units_config_sets_unit_and_display() {
+        let config = "[default]
+squat = 140
+bench_press = 100
+deadlift = 170
+overhead_press = 65
+";
+        assert_eq!(
+            Config::parse(config, "training_max.toml")
+                .unwrap()
+                .units()
+                .unwrap(),
+            Units::default()
+        );
+
+        let kg = format!("{}\n[units]\nunit = \"kg\"\ndisplay = \"both\"", config);
+        assert_eq!(
+            Config::parse(&kg, "training_max.toml")
+                .unwrap()
+                .units()
+                .unwrap(),
+            Units {
+                unit: Unit::Kilogram,
+                display: Some(DisplayUnit::Both),
+            }
+        );
+
+        let bad = format!("{}\n[units]\nunit = \"stone\"", config);
+        let err = Config::parse(&bad, "training_max.toml")
+            .unwrap()
+            .units()
+            .unwrap_err();
+        assert!(err.to_string().contains(
+            "Invalid unit 'stone'. Valid values are: lb/kg. (in [units] of training_max.toml)"
+        ));
+    }
+
+    #[test]
+    fn rounding_config_sets_increment_mode_and_per_lift_overrides() {
+        let config = "[default]
+squat = 325
+bench_press = 235
+deadlift = 365
+overhead_press = 170
+";
+        assert_eq!(
+            Config::parse(config, "training_max.toml")
+                .unwrap()
+                .rounding(Unit::Pound)
+                .unwrap(),
+            RoundingRules::default()
+        );
+
+        let custom = format!(
+            "{}\n[rounding]\nincrement = 5\nmode = \"down\"\n\n[rounding.overhead_press]\nincrement = 2",
+            config
+        );
+        let rules = Config::parse(&custom, "training_max.toml")
+            .unwrap()
+            .rounding(Unit::Pound)
+            .unwrap();
+        assert_eq!(
+            rules.default,
+            Rounding {
+                increment: 5.0,
+                mode: RoundingMode::Down
+            }
+        );
+        assert_eq!(
+            rules.for_lift(&Lift::OverheadPress),
+            &Rounding {
+                increment: 2.0,
+                mode: RoundingMode::Down
+            }
+        );
+        assert_eq!(rules.for_lift(&Lift::FrontSquat), &rules.default);
+    }
+
+    #[test]
+    fn rounding_config_rejects_bad_increments_modes_and_lifts() {
+        let config = "[default]
+squat = 325
+bench_press = 235
+deadlift = 365
+overhead_press = 170
+";
+        let zero = format!("{}\n[rounding]\nincrement = 0", config);
+        let err = Config::parse(&zero, "training_max.toml")
+            .unwrap()
+            .rounding(Unit::Pound)
+            .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("increment must be a positive number, got 0"));
+
+        let mode = format!("{}\n[rounding.squat]\nmode = \"sideways\"", config);
+        let err = Config::parse(&mode, "training_max.toml")
+            .unwrap()
+            .rounding(Unit::Pound)
+            .unwrap_err();
+        assert!(err.to_string().contains("Invalid rounding mode 'sideways'"));
+        assert!(err
+            .to_string()
+            .contains("(in [rounding.squat] of training_max.toml)"));
+
+        let lift = format!("{}\n[rounding.fakelift]\nincrement = 5", config);
+        let err = Config::parse(&lift, "training_max.toml")
+            .unwrap()
+            .rounding(Unit::Pound)
+            .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Unknown lift 'fakelift' in [rounding]"));
+    }
+
+    #[test]
+    fn warm_up_config_sets_ramp_empty_bar_and_per_lift_overrides() {
+        let config = "[default]
+squat = 325
+bench_press = 235
+deadlift = 365
+overhead_press = 170
+";
+        assert_eq!(
+            Config::parse(config, "training_max.toml")
+                .unwrap()
+                .warm_up(45.0)
+                .unwrap(),
+            WarmUpRules::default()
+        );
+
+        let custom = format!(
+            "{}\n[warm_up]\nsets = [\"30x5\", \"45x5\", \"60x3\"]\nempty_bar = true\nmin_gap_percent = 5\n\n\
+             [warm_up.deadlift]\nempty_bar = false\nsets = [\"40x5\", \"60x3\"]",
+            config
+        );
+        let rules = Config::parse(&custom, "training_max.toml")
+            .unwrap()
+            .warm_up(20.0)
+            .unwrap();
+        assert_eq!(
+            rules.default.sets,
+            vec![
+                SetScheme::new(0.3, 5, false),
+                SetScheme::new(0.45, 5, false),
+                SetScheme::new(0.6, 3, false)
+            ]
+        );
+        assert_eq!(rules.default.empty_bar_reps, Some(DEFAULT_EMPTY_BAR_REPS));
+        assert_eq!(rules.default.bar, 20.0);
+        assert!((rules.default.min_gap - 0.05).abs() < 0.0001);
+
+        let deadlift = rules.for_lift(&Lift::Deadlift);
+        assert_eq!(
+            deadlift.sets,
+            vec![SetScheme::new(0.4, 5, false), SetScheme::new(0.6, 3, false)]
+        );
+        assert_eq!(deadlift.empty_bar_reps, None);
+        assert_eq!(deadlift.min_gap, rules.default.min_gap);
+        assert_eq!(rules.for_lift(&Lift::Squat), &rules.default);
+
+        // the 65% first working set of week 1 keeps the 60% set with a 5% gap
+        let mut tms = HashMap::new();
+        tms.insert(Lift::Squat, 300.0);
+        let mut scheme = Scheme::default();
+        scheme.set_warm_up(rules);
+        let set_groups = generate_primary_sets(
+            &Lift::Squat,
+            &Week::Week1,
+            &PrimaryVariant::Standard,
+            0,
+            &scheme,
+            &RoundingRules::default(),
+            &tms,
+        )
+        .unwrap();
+        let warm_up: Vec<(f32, i8)> = set_groups
+            .iter()
+            .take(4)
+            .map(|sg| (sg.weight, sg.reps))
+            .collect();
+        assert_eq!(warm_up, vec![(20.0, 10), (90.0, 5), (135.0, 5), (180.0, 3)]);
+    }
+
+    #[test]
+    fn warm_up_config_rejects_bad_sets_gaps_and_lifts() {
+        let config = "[default]
+squat = 325
+bench_press = 235
+deadlift = 365
+overhead_press = 170
+";
+        let amrap = format!("{}\n[warm_up.squat]\nsets = [\"50x5+\"]", config);
+        let err = Config::parse(&amrap, "training_max.toml")
+            .unwrap()
+            .warm_up(45.0)
+            .unwrap_err();
+        assert!(err.to_string().contains(
+            "warm-up set '50x5+' must not be AMRAP (in [warm_up.squat] of training_max.toml)"
+        ));
+
+        let gap = format!("{}\n[warm_up]\nmin_gap_percent = -5", config);
+        let err = Config::parse(&gap, "training_max.toml")
+            .unwrap()
+            .warm_up(45.0)
+            .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("min_gap_percent must be between 0 and 100, got -5"));
+
+        let lift = format!("{}\n[warm_up.front_squat]\nempty_bar = true", config);
+        let err = Config::parse(&lift, "training_max.toml")
+            .unwrap()
+            .warm_up(45.0)
+            .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Unknown primary lift 'front_squat' in [warm_up]"));
+    }
+
+    #[test]
+    fn decimal_training_maxes_round_and_progress_in_kilograms() {
+        let config = "[default]
+squat = 140
+bench_press = 102.5
+deadlift = 170
+overhead_press = 62.5
+
+[units]
+unit = \"kg\"
+";
+        let training_maxes = parse_training_maxes_from_str(config, "training_max.toml").unwrap();
+        assert_eq!(training_maxes[&Lift::BenchPress], 102.5);
+
+        let rounding = Config::parse(config, "training_max.toml")
+            .unwrap()
+            .rounding(Unit::Kilogram)
+            .unwrap();
+        assert_eq!(rounding.default.increment, 2.5);
+        let micro = format!("{}\n[rounding]\nincrement = 1.25", config);
+        let rounding = Config::parse(&micro, "training_max.toml")
+            .unwrap()
+            .rounding(Unit::Kilogram)
+            .unwrap();
+        assert_eq!(
+            rounding.for_lift(&Lift::BenchPress).round(102.5 * 0.65),
+            66.25
+        );
+
+        let progression = Config::parse(config, "training_max.toml")
+            .unwrap()
+            .progression(None, None)
+            .unwrap();
+        let (contents, _) =
+            progress_training_maxes(config, "training_max.toml", &progression).unwrap();
+        assert!(contents
+            .contains("squat = 145\nbench_press = 105\ndeadlift = 175\noverhead_press = 65\n"));
+        let (contents, _) =
+            progress_training_maxes(&contents, "training_max.toml", &progression).unwrap();
+        assert!(contents.contains("bench_press = 107.5\n"));
+
+        let nan = config.replace("squat = 140", "squat = nan");
+        let err = parse_training_maxes_from_str(&nan, "training_max.toml").unwrap_err();
+        assert!(err.to_string().contains(
+            "Training max for 'squat' in training_max.toml must be a positive number, got NaN"
+        ));
+    }
+
+    #[test]
+    fn parse_training_maxes_rejects_config_without_primary_keys() {
+        let config = "[default]\nsquat = 325
+bench_press = 235
+front_squat = 215";
+        let err = parse_training_maxes_from_str(config, "training_max.toml").unwrap_err();
+        let msg = err.to_string();
+        assert!(msg.contains("deadlift"));
+        assert!(msg.contains("overhead press"));
+        assert!(msg.contains("Missing required primary lift training max"));
+    }
+}
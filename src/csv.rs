@@ -0,0 +1,214 @@
+//! CSV rendering of workouts, one row per set.
+
+use chrono::NaiveDate;
+
+use crate::schedule::ScheduledSession;
+use crate::units::Unit;
+use crate::workout::{Exercise, Workout};
+
+/// Columns of every row, as documented in the README
+pub const HEADER: &str =
+    "date,session,week,seventh_week,section,lift,weight,unit,reps,set,amrap,percent_tm";
+
+/// Quotes a field that would otherwise be split or misread by a spreadsheet.
+fn field(text: &str) -> String {
+    if text.contains([',', '"', '\n']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_owned()
+    }
+}
+
+/// Sets printed by `--format csv`, one row per set for pasting into a
+/// spreadsheet
+pub struct Table {
+    /// Unit of every weight in the table
+    unit: Unit,
+    rows: Vec<String>,
+}
+
+impl Table {
+    /// An empty table whose weights are in `unit`
+    pub fn new(unit: Unit) -> Table {
+        Table { unit, rows: vec![] }
+    }
+
+    /// Adds a row for each weighted set of a workout, with its place in the
+    /// schedule when it's part of a cycle and its date when it's known. Sets are
+    /// numbered from 1 within each section, and only the last set of an AMRAP
+    /// set group is flagged.
+    pub fn push(
+        &mut self,
+        workout: &Workout,
+        session: Option<&ScheduledSession>,
+        date: Option<NaiveDate>,
+    ) {
+        let date = date.map_or(String::new(), |date| date.to_string());
+        let session = session.map_or(String::new(), |session| session.number.to_string());
+        let seventh_week = workout
+            .week
+            .seventh_week()
+            .map_or("", |protocol| protocol.name());
+        let unit = self.unit.to_string();
+        for section in workout.sections.iter() {
+            let mut number = 0;
+            for exercise in section.exercises.iter() {
+                let set_group = match exercise {
+                    Exercise::Sets { set_group, .. } => set_group,
+                    Exercise::Text(_) => continue,
+                };
+                // "5" or "3-5"
+                let mut reps = set_group.reps.to_string();
+                if let Some(max_reps) = set_group.max_reps {
+                    reps += &format!("-{}", max_reps);
+                }
+                // e.g. "65" or "62.5"
+                let percent = set_group.scalar.map_or(String::new(), |scalar| {
+                    ((scalar * 1000.0).round() / 10.0).to_string()
+                });
+                let lift = set_group.lift.config_key();
+                for i in 1..=set_group.sets {
+                    number += 1;
+                    self.rows.push(
+                        [
+                            date.as_str(),
+                            session.as_str(),
+                            &workout.week.number().to_string(),
+                            seventh_week,
+                            &field(&section.name),
+                            lift,
+                            &set_group.weight.to_string(),
+                            &unit,
+                            &reps,
+                            &number.to_string(),
+                            if set_group.amrap && i == set_group.sets {
+                                "true"
+                            } else {
+                                "false"
+                            },
+                            &percent,
+                        ]
+                        .join(","),
+                    );
+                }
+            }
+        }
+    }
+
+    /// The header and every row added so far, one line each
+    pub fn to_csv(&self) -> String {
+        let mut s = HEADER.to_owned() + "\n";
+        for row in self.rows.iter() {
+            s += row;
+            s += "\n";
+        }
+        s
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lifts::{
+        generate_primary_sets, generate_supplemental_sets, BbbPercent, Lift, PrimaryVariant,
+        SeventhWeek, Supplemental, Week,
+    };
+    use crate::rounding::RoundingRules;
+    use crate::schedule::Schedule;
+    use crate::scheme::Scheme;
+    use crate::workout::Section;
+    use std::collections::HashMap;
+
+    #[test]
+    fn writes_a_row_per_set() {
+        let mut training_maxes = HashMap::new();
+        training_maxes.insert(Lift::Squat, 325.0);
+        let primary_sets = generate_primary_sets(
+            &Lift::Squat,
+            &Week::Week3,
+            &PrimaryVariant::Standard,
+            0,
+            &Scheme::default(),
+            &RoundingRules::default(),
+            &training_maxes,
+        )
+        .unwrap();
+        let bbb = generate_supplemental_sets(
+            &Lift::Squat,
+            &Week::Week3,
+            &Supplemental::BoringButBig(BbbPercent::Ascending),
+            &Scheme::default(),
+            &RoundingRules::default(),
+            &training_maxes,
+        )
+        .unwrap();
+        let mut primary = Section::new("Primary lift");
+        primary
+            .exercises
+            .extend(primary_sets.into_iter().skip(3).map(Exercise::from));
+        let mut supplemental = Section::new("Supplemental, BBB");
+        supplemental
+            .exercises
+            .extend(bbb.into_iter().map(Exercise::from));
+        let mut core = Section::new("Core");
+        core.exercises
+            .push(Exercise::Text("bird dog, 3x10/side".to_owned()));
+        let workout = Workout {
+            primary_lift: Lift::Squat,
+            week: Week::Week3,
+            sections: vec![primary, supplemental, core],
+        };
+
+        let mut table = Table::new(Unit::Pound);
+        table.push(&workout, None, NaiveDate::from_ymd_opt(2025, 3, 1));
+        assert_eq!(
+            table.to_csv(),
+            "date,session,week,seventh_week,section,lift,weight,unit,reps,set,amrap,percent_tm\n\
+             2025-03-01,,3,,Primary lift,squat,244,lb,5,1,false,75\n\
+             2025-03-01,,3,,Primary lift,squat,276,lb,3,2,false,85\n\
+             2025-03-01,,3,,Primary lift,squat,309,lb,1,3,true,95\n\
+             2025-03-01,,3,,\"Supplemental, BBB\",squat,228,lb,10,1,false,70\n\
+             2025-03-01,,3,,\"Supplemental, BBB\",squat,228,lb,10,2,false,70\n\
+             2025-03-01,,3,,\"Supplemental, BBB\",squat,228,lb,10,3,false,70\n\
+             2025-03-01,,3,,\"Supplemental, BBB\",squat,228,lb,10,4,false,70\n\
+             2025-03-01,,3,,\"Supplemental, BBB\",squat,228,lb,10,5,false,70\n"
+        );
+    }
+
+    #[test]
+    fn cycle_rows_have_the_session_and_seventh_week() {
+        let mut training_maxes = HashMap::new();
+        training_maxes.insert(Lift::BenchPress, 235.0);
+        let week = Week::Week7(SeventhWeek::TmTest);
+        let primary_sets = generate_primary_sets(
+            &Lift::BenchPress,
+            &week,
+            &PrimaryVariant::Standard,
+            0,
+            &Scheme::default(),
+            &RoundingRules::default(),
+            &training_maxes,
+        )
+        .unwrap();
+        let mut primary = Section::new("Primary lift");
+        primary
+            .exercises
+            .push(Exercise::from(primary_sets.last().unwrap().clone()));
+        let workout = Workout {
+            primary_lift: Lift::BenchPress,
+            week,
+            sections: vec![primary],
+        };
+
+        let mut table = Table::new(Unit::Pound);
+        table.push(
+            &workout,
+            Some(&Schedule::default().session(3).unwrap()),
+            None,
+        );
+        assert_eq!(
+            table.to_csv().lines().nth(1),
+            Some(",3,7,tm-test,Primary lift,bench_press,235,lb,5,1,false,100")
+        );
+    }
+}
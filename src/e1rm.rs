@@ -0,0 +1,176 @@
+//! Estimated one-rep maxes from sets of several reps, and the reps needed to
+//! beat a best estimate.
+
+use std::fmt;
+use strum_macros::EnumString;
+
+use crate::lifts::Lift;
+use crate::training_log::LoggedSession;
+use crate::units::Unit;
+
+/// Most reps considered when looking for a rep target on an AMRAP set
+const MAX_REP_TARGET: i8 = 30;
+
+/// Formula used to estimate a one-rep max from a set of several reps
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumString)]
+pub enum E1rmFormula {
+    /// weight x reps x 0.0333 + weight
+    #[strum(serialize = "wendler", serialize = "531")]
+    Wendler,
+    /// weight x (1 + reps / 30)
+    #[strum(serialize = "epley")]
+    Epley,
+    /// weight x 36 / (37 - reps)
+    #[strum(serialize = "brzycki")]
+    Brzycki,
+    /// weight x reps ^ 0.1
+    #[strum(serialize = "lombardi")]
+    Lombardi,
+}
+
+impl fmt::Display for E1rmFormula {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s: &str = match self {
+            E1rmFormula::Wendler => "Wendler",
+            E1rmFormula::Epley => "Epley",
+            E1rmFormula::Brzycki => "Brzycki",
+            E1rmFormula::Lombardi => "Lombardi",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+impl E1rmFormula {
+    /// Estimated one-rep max for `reps` reps at `weight`, or `None` when the
+    /// formula isn't defined for that many reps.
+    pub fn estimate(&self, weight: f32, reps: i8) -> Option<f32> {
+        if reps <= 0 {
+            return None;
+        }
+        let reps = reps as f32;
+        match self {
+            E1rmFormula::Wendler => Some(weight * reps * 0.0333 + weight),
+            E1rmFormula::Epley => Some(weight * (1.0 + reps / 30.0)),
+            // the denominator reaches zero at 37 reps
+            E1rmFormula::Brzycki if reps >= 37.0 => None,
+            E1rmFormula::Brzycki => Some(weight * 36.0 / (37.0 - reps)),
+            E1rmFormula::Lombardi => Some(weight * reps.powf(0.1)),
+        }
+    }
+
+    /// Fewest reps at `weight` whose estimated one-rep max beats `target`, or
+    /// `None` if more than 30 reps would be needed.
+    pub fn reps_to_beat(&self, weight: f32, target: f32) -> Option<i8> {
+        (1..=MAX_REP_TARGET).find(|reps| {
+            self.estimate(weight, *reps)
+                .is_some_and(|e1rm| e1rm > target)
+        })
+    }
+
+    /// Best estimated one-rep max in `unit` from the AMRAP sets logged for `lift`,
+    /// leaving out TM test sessions.
+    pub fn best_logged(&self, lift: &Lift, sessions: &[LoggedSession], unit: Unit) -> Option<f32> {
+        sessions
+            .iter()
+            .filter(|session| session.lift == *lift && !session.is_tm_test())
+            .flat_map(|session| session.sets.iter().map(move |set| (session.unit, set)))
+            .filter_map(|(logged_unit, set)| {
+                set.actual_reps
+                    .and_then(|reps| self.estimate(set.weight, reps))
+                    .map(|e1rm| logged_unit.convert(e1rm, unit))
+            })
+            .reduce(f32::max)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lifts::SeventhWeek;
+    use crate::training_log::LoggedSet;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn formulas_match_published_estimates() {
+        assert_eq!(
+            E1rmFormula::Wendler.estimate(300.0, 5).unwrap().round(),
+            350.0
+        );
+        assert_eq!(
+            E1rmFormula::Epley.estimate(300.0, 5).unwrap().round(),
+            350.0
+        );
+        assert_eq!(
+            E1rmFormula::Brzycki.estimate(300.0, 5).unwrap().round(),
+            338.0
+        );
+        assert_eq!(
+            E1rmFormula::Lombardi.estimate(300.0, 5).unwrap().round(),
+            352.0
+        );
+        assert_eq!(E1rmFormula::Brzycki.estimate(300.0, 1), Some(300.0));
+        assert_eq!(E1rmFormula::Brzycki.estimate(100.0, 37), None);
+        assert_eq!(E1rmFormula::Epley.estimate(300.0, 0), None);
+    }
+
+    #[test]
+    fn reps_to_beat_finds_smallest_rep_count() {
+        // 276 x 7 = 340.3, 276 x 6 = 331.1
+        assert_eq!(E1rmFormula::Wendler.reps_to_beat(276.0, 335.0), Some(7));
+        assert_eq!(E1rmFormula::Wendler.reps_to_beat(276.0, 200.0), Some(1));
+        assert_eq!(E1rmFormula::Wendler.reps_to_beat(100.0, 1000.0), None);
+    }
+
+    #[test]
+    fn best_logged_uses_recorded_amrap_sets_for_the_lift() {
+        let set = |weight, actual_reps: Option<i8>| LoggedSet {
+            weight,
+            sets: 1,
+            reps: 5,
+            max_reps: None,
+            amrap: actual_reps.is_some(),
+            actual_reps,
+            note: None,
+        };
+        let session = |lift, sets| LoggedSession {
+            date: NaiveDate::from_ymd_opt(2025, 3, 1).unwrap(),
+            lift,
+            week: 1,
+            seventh_week: None,
+            unit: Unit::Pound,
+            notes: None,
+            sets,
+        };
+        let sessions = vec![
+            session(Lift::Squat, vec![set(244.0, None), set(276.0, Some(8))]),
+            session(Lift::Squat, vec![set(309.0, Some(3))]),
+            session(Lift::Deadlift, vec![set(400.0, Some(10))]),
+        ];
+        let best = E1rmFormula::Epley
+            .best_logged(&Lift::Squat, &sessions, Unit::Pound)
+            .unwrap();
+        assert_eq!(best.round(), 350.0);
+        assert_eq!(
+            E1rmFormula::Epley.best_logged(&Lift::BenchPress, &sessions, Unit::Pound),
+            None
+        );
+
+        // sessions logged in pounds count toward a best in kilograms
+        let best = E1rmFormula::Epley
+            .best_logged(&Lift::Squat, &sessions, Unit::Kilogram)
+            .unwrap();
+        assert_eq!(best.round(), 159.0);
+
+        // a TM test isn't a PR attempt
+        let mut sessions = sessions;
+        sessions.push(LoggedSession {
+            week: 7,
+            seventh_week: Some(SeventhWeek::TmTest),
+            ..session(Lift::Squat, vec![set(325.0, Some(8))])
+        });
+        let best = E1rmFormula::Epley
+            .best_logged(&Lift::Squat, &sessions, Unit::Pound)
+            .unwrap();
+        assert_eq!(best.round(), 350.0);
+    }
+}
@@ -0,0 +1,24 @@
+//! Output formats for sessions and cycles.
+
+use strum_macros::EnumString;
+
+/// How sessions and cycles are printed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, EnumString)]
+pub enum Format {
+    /// Plain text for the terminal
+    #[default]
+    #[strum(serialize = "text")]
+    Text,
+    /// The JSON document described in the README
+    #[strum(serialize = "json")]
+    Json,
+    /// Markdown headings, tables and task lists, e.g. for a wiki
+    #[strum(serialize = "markdown", serialize = "md")]
+    Markdown,
+    /// One row per set, for spreadsheets
+    #[strum(serialize = "csv")]
+    Csv,
+    /// A self-contained, printable page
+    #[strum(serialize = "html")]
+    Html,
+}
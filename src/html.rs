@@ -0,0 +1,223 @@
+//! HTML rendering of workouts as a printable sheet.
+
+use crate::plates::PlateInventory;
+use crate::schedule::ScheduledSession;
+use crate::units::Units;
+use crate::workout::{Exercise, Section, Workout};
+
+/// Inline stylesheet, sized to fit several workouts on each printed page
+const STYLE: &str = "\
+body { font-family: sans-serif; font-size: 10pt; margin: 1.5em; color: #000; }
+h1 { font-size: 16pt; margin: 0 0 0.3em; }
+h2 { font-size: 12pt; margin: 0 0 0.3em; }
+h3 { font-size: 10pt; margin: 0.6em 0 0.2em; }
+.lifter { margin: 0 0 1em; }
+.blank { display: inline-block; width: 14em; border-bottom: 1px solid #000; }
+.workouts { display: grid; grid-template-columns: repeat(auto-fill, minmax(22em, 1fr)); gap: 1em; }
+.workout { border: 1px solid #000; padding: 0.5em; break-inside: avoid; }
+.session { font-size: 8pt; margin: 0 0 0.2em; }
+table { border-collapse: collapse; width: 100%; }
+th, td { border-bottom: 1px solid #999; padding: 0.15em 0.3em; text-align: left; vertical-align: top; }
+td.number { text-align: right; }
+ul { margin: 0; padding-left: 1.2em; }
+.check { display: inline-block; width: 0.9em; height: 0.9em; border: 1px solid #000; margin-right: 0.2em; }
+.amrap { display: inline-block; width: 2.5em; height: 1.2em; border: 2px solid #000; vertical-align: middle; }
+@media print {
+  @page { margin: 1cm; }
+  body { margin: 0; }
+}
+";
+
+/// Escapes text for use in HTML content or attributes.
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Printable sheet of workouts printed by `--format html`, as one
+/// self-contained page
+pub struct Sheet<'a> {
+    title: String,
+    units: &'a Units,
+    /// Plate inventory for the plates column, when there is one
+    plates: Option<&'a PlateInventory>,
+    workouts: Vec<String>,
+}
+
+impl<'a> Sheet<'a> {
+    /// An empty sheet titled `title`, with a plates column when `plates` is given
+    pub fn new(title: &str, units: &'a Units, plates: Option<&'a PlateInventory>) -> Sheet<'a> {
+        Sheet {
+            title: title.to_owned(),
+            units,
+            plates,
+            workouts: vec![],
+        }
+    }
+
+    /// Table of a section's sets. Each set gets a box to tick, or a wider box
+    /// to write the reps done on an AMRAP set.
+    fn sets_table(&self, section: &Section) -> String {
+        let mut s = String::from("<table>\n<tr><th>Lift</th><th>Weight</th><th>Sets × reps</th>");
+        if self.plates.is_some() {
+            s += &format!("<th>Plates ({})</th>", self.units.unit);
+        }
+        s += "<th>Done</th><th>Notes</th></tr>\n";
+        for exercise in section.exercises.iter() {
+            let (set_group, note) = match exercise {
+                Exercise::Sets { set_group, note } => (set_group, note),
+                Exercise::Text(_) => continue,
+            };
+            // "5", "3-5" or "5+", after the number of sets when there's more than one
+            let mut reps = set_group.reps.to_string();
+            if let Some(max_reps) = set_group.max_reps {
+                reps += &format!("-{}", max_reps);
+            }
+            if set_group.amrap {
+                reps += "+";
+            }
+            if set_group.sets > 1 {
+                reps = format!("{} × {}", set_group.sets, reps);
+            }
+            s += &format!(
+                "<tr><td>{}</td><td class=\"number\">{}</td><td class=\"number\">{}</td>",
+                set_group.lift,
+                escape(&self.units.format(set_group.weight)),
+                reps
+            );
+            if let Some(plates) = self.plates {
+                s += &format!("<td>{}</td>", set_group.plates(plates));
+            }
+            s += "<td>";
+            for i in 1..=set_group.sets {
+                match set_group.amrap && i == set_group.sets {
+                    true => s += "<span class=\"amrap\"></span>",
+                    false => s += "<span class=\"check\"></span>",
+                }
+            }
+            let notes: Vec<&str> = set_group.note.into_iter().chain(note.as_deref()).collect();
+            s += &format!("</td><td>{}</td></tr>\n", escape(&notes.join("; ")));
+        }
+        s += "</table>\n";
+        s
+    }
+
+    /// Adds a workout, headed by its place in the schedule when it's part of a
+    /// cycle. Sets are listed in a table per section, and every other exercise
+    /// in a list.
+    pub fn push(&mut self, workout: &Workout, session: Option<&ScheduledSession>) {
+        let mut s = String::from("<article class=\"workout\">\n");
+        if let Some(session) = session {
+            s += &format!(
+                "<p class=\"session\">{}</p>\n",
+                escape(&session.to_string())
+            );
+        }
+        s += &format!("<h2>{}</h2>\n", escape(&workout.title()));
+        for section in workout.sections.iter() {
+            s += &format!("<h3>{}</h3>\n", escape(&section.name));
+            if section
+                .exercises
+                .iter()
+                .any(|exercise| matches!(exercise, Exercise::Sets { .. }))
+            {
+                s += &self.sets_table(section);
+            }
+            let texts: Vec<&String> = section
+                .exercises
+                .iter()
+                .filter_map(|exercise| match exercise {
+                    Exercise::Text(text) => Some(text),
+                    Exercise::Sets { .. } => None,
+                })
+                .collect();
+            if !texts.is_empty() {
+                s += "<ul>\n";
+                for text in texts {
+                    s += &format!("<li>{}</li>\n", escape(text));
+                }
+                s += "</ul>\n";
+            }
+        }
+        s += "</article>\n";
+        self.workouts.push(s);
+    }
+
+    /// The whole page, with every workout added so far
+    pub fn to_html(&self) -> String {
+        let mut s =
+            String::from("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n");
+        s += &format!("<title>{}</title>\n", escape(&self.title));
+        s += &format!("<style>\n{}</style>\n</head>\n<body>\n", STYLE);
+        s += &format!("<h1>{}</h1>\n", escape(&self.title));
+        s += "<p class=\"lifter\">Name <span class=\"blank\"></span> Start date <span class=\"blank\"></span></p>\n";
+        s += "<main class=\"workouts\">\n";
+        for workout in self.workouts.iter() {
+            s += workout;
+        }
+        s += "</main>\n</body>\n</html>\n";
+        s
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lifts::{generate_primary_sets, Lift, PrimaryVariant, Week};
+    use crate::rounding::RoundingRules;
+    use crate::schedule::Schedule;
+    use crate::scheme::Scheme;
+    use std::collections::HashMap;
+
+    #[test]
+    fn sheet_has_plates_boxes_and_escaped_text() {
+        let mut training_maxes = HashMap::new();
+        training_maxes.insert(Lift::Squat, 325.0);
+        let set_groups = generate_primary_sets(
+            &Lift::Squat,
+            &Week::Week1,
+            &PrimaryVariant::Standard,
+            0,
+            &Scheme::default(),
+            &RoundingRules::default(),
+            &training_maxes,
+        )
+        .unwrap();
+        let mut primary = Section::new("Primary lift");
+        primary.exercises.push(Exercise::Sets {
+            set_group: set_groups.last().unwrap().clone(),
+            note: Some("3 reps to beat best e1RM of 340".to_owned()),
+        });
+        let mut assistance = Section::new("Assistance lifts");
+        assistance.exercises.push(Exercise::Text(
+            "RDLs, up to 225, 2x10 & <chin-ups>".to_owned(),
+        ));
+        let workout = Workout {
+            primary_lift: Lift::Squat,
+            week: Week::Week1,
+            sections: vec![primary, assistance],
+        };
+
+        let units = Units::default();
+        let plates = PlateInventory::default();
+        let mut sheet = Sheet::new("5/3/1 cycle", &units, Some(&plates));
+        sheet.push(&workout, Some(&Schedule::default().session(1).unwrap()));
+        let html = sheet.to_html();
+        assert!(html.starts_with("<!DOCTYPE html>\n"));
+        assert!(html.contains("<style>\n"));
+        assert!(html.contains(
+            "<p class=\"session\">Session 1 (week 1, day 1): squat, cycle 1 week 1</p>\n"
+        ));
+        assert!(html.contains("<h2>squat week 1</h2>\n"));
+        assert!(html.contains("<th>Plates (lb)</th>"));
+        assert!(html.contains(
+            "<tr><td>squat</td><td class=\"number\">276</td><td class=\"number\">5+</td>\
+             <td>45 45 25 per side; not loadable, closest is 275</td>\
+             <td><span class=\"amrap\"></span></td><td>3 reps to beat best e1RM of 340</td></tr>\n"
+        ));
+        assert!(html.contains("<li>RDLs, up to 225, 2x10 &amp; &lt;chin-ups&gt;</li>\n"));
+        assert!(html.ends_with("</html>\n"));
+    }
+}
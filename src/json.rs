@@ -0,0 +1,224 @@
+//! JSON rendering of workouts, for other tools to read.
+
+use serde::Serialize;
+
+use crate::plates::PlateInventory;
+use crate::schedule::ScheduledSession;
+use crate::units::Unit;
+use crate::workout::{Exercise, Workout};
+
+/// Version of the document layout, bumped whenever a field is removed or
+/// changes meaning. New fields may be added without a bump.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Workouts printed by `--format json`, as documented in the README
+#[derive(Serialize)]
+pub struct Document {
+    schema_version: u32,
+    /// Seed that reproduces the random assistance and core choices
+    seed: u64,
+    /// Unit of every weight in the document
+    unit: Unit,
+    workouts: Vec<WorkoutJson>,
+}
+
+#[derive(Serialize)]
+struct WorkoutJson {
+    /// Where the workout falls in the training schedule; `null` for a single session
+    session: Option<SessionJson>,
+    primary_lift: &'static str,
+    week: u8,
+    /// `"deload"` or `"tm-test"` in the 7th week, otherwise `null`
+    seventh_week: Option<&'static str>,
+    sections: Vec<SectionJson>,
+}
+
+#[derive(Serialize)]
+struct SessionJson {
+    number: u32,
+    cycle: u32,
+    calendar_week: u32,
+    day: u32,
+}
+
+#[derive(Serialize)]
+struct SectionJson {
+    name: String,
+    exercises: Vec<ExerciseJson>,
+}
+
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ExerciseJson {
+    Sets {
+        lift: &'static str,
+        weight: f32,
+        sets: i8,
+        reps: i8,
+        /// Upper end of a rep range, e.g. 5 for "x3-5"
+        max_reps: Option<i8>,
+        amrap: bool,
+        notes: Vec<String>,
+        /// Plates per side with `--plates`, otherwise `null`
+        plates: Option<PlatesJson>,
+    },
+    Text {
+        text: String,
+    },
+}
+
+#[derive(Serialize)]
+struct PlatesJson {
+    per_side: Vec<f32>,
+    /// Weight as loaded; less than the set's weight when it isn't loadable
+    loaded: f32,
+}
+
+impl Document {
+    /// An empty document for workouts generated with `seed`, weighed in `unit`
+    pub fn new(seed: u64, unit: Unit) -> Document {
+        Document {
+            schema_version: SCHEMA_VERSION,
+            seed,
+            unit,
+            workouts: vec![],
+        }
+    }
+
+    /// Adds a workout, with its place in the schedule when it's part of a
+    /// cycle. Plate breakdowns are included when `plates` is given.
+    pub fn push(
+        &mut self,
+        workout: &Workout,
+        session: Option<&ScheduledSession>,
+        plates: Option<&PlateInventory>,
+    ) {
+        let sections = workout
+            .sections
+            .iter()
+            .map(|section| SectionJson {
+                name: section.name.clone(),
+                exercises: section
+                    .exercises
+                    .iter()
+                    .map(|exercise| match exercise {
+                        Exercise::Sets { set_group, note } => ExerciseJson::Sets {
+                            lift: set_group.lift.config_key(),
+                            weight: set_group.weight,
+                            sets: set_group.sets,
+                            reps: set_group.reps,
+                            max_reps: set_group.max_reps,
+                            amrap: set_group.amrap,
+                            notes: set_group
+                                .note
+                                .map(str::to_owned)
+                                .into_iter()
+                                .chain(note.clone())
+                                .collect(),
+                            plates: plates.map(|inventory| {
+                                let breakdown = set_group.plates(inventory);
+                                PlatesJson {
+                                    per_side: breakdown.per_side,
+                                    loaded: breakdown.loaded,
+                                }
+                            }),
+                        },
+                        Exercise::Text(text) => ExerciseJson::Text { text: text.clone() },
+                    })
+                    .collect(),
+            })
+            .collect();
+
+        self.workouts.push(WorkoutJson {
+            session: session.map(|session| SessionJson {
+                number: session.number,
+                cycle: session.cycle,
+                calendar_week: session.calendar_week,
+                day: session.day,
+            }),
+            primary_lift: workout.primary_lift.config_key(),
+            week: workout.week.number(),
+            seventh_week: workout.week.seventh_week().map(|protocol| protocol.name()),
+            sections,
+        });
+    }
+
+    /// The document as pretty-printed JSON
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("workouts always serialize to JSON")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lifts::{generate_primary_sets, Lift, PrimaryVariant, Week};
+    use crate::rounding::RoundingRules;
+    use crate::scheme::Scheme;
+    use crate::workout::Section;
+    use serde_json::{json, Value};
+    use std::collections::HashMap;
+
+    #[test]
+    fn documents_sets_and_text_exercises() {
+        let mut training_maxes = HashMap::new();
+        training_maxes.insert(Lift::Squat, 325.0);
+        let set_groups = generate_primary_sets(
+            &Lift::Squat,
+            &Week::Week1,
+            &PrimaryVariant::Standard,
+            0,
+            &Scheme::default(),
+            &RoundingRules::default(),
+            &training_maxes,
+        )
+        .unwrap();
+        let mut primary = Section::new("Primary lift");
+        primary
+            .exercises
+            .extend(set_groups.into_iter().map(|set_group| Exercise::Sets {
+                set_group,
+                note: None,
+            }));
+        let mut assistance = Section::new("Assistance lifts");
+        assistance
+            .exercises
+            .push(Exercise::Text("chin-ups, 2x10".to_owned()));
+        let workout = Workout {
+            primary_lift: Lift::Squat,
+            week: Week::Week1,
+            sections: vec![primary, assistance],
+        };
+
+        let mut document = Document::new(3, Unit::Pound);
+        document.push(&workout, None, Some(&PlateInventory::default()));
+        let value: Value = serde_json::from_str(&document.to_json()).unwrap();
+        assert_eq!(value["schema_version"], json!(1));
+        assert_eq!(value["seed"], json!(3));
+        assert_eq!(value["unit"], json!("lb"));
+
+        let workout = &value["workouts"][0];
+        assert_eq!(workout["session"], Value::Null);
+        assert_eq!(workout["primary_lift"], json!("squat"));
+        assert_eq!(workout["week"], json!(1));
+        let top_set = &workout["sections"][0]["exercises"][4];
+        assert_eq!(
+            top_set,
+            &json!({
+                "type": "sets",
+                "lift": "squat",
+                "weight": 276.0,
+                "sets": 1,
+                "reps": 5,
+                "max_reps": null,
+                "amrap": true,
+                "notes": [],
+                "plates": { "per_side": [45.0, 45.0, 25.0], "loaded": 275.0 },
+            })
+        );
+        assert_eq!(
+            workout["sections"][1],
+            json!({ "name": "Assistance lifts", "exercises": [{ "type": "text", "text": "chin-ups, 2x10" }] })
+        );
+    }
+}
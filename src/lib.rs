@@ -0,0 +1,61 @@
+//! Generate 5/3/1 training sessions and cycles from training maxes.
+//!
+//! The `five-three-one` command line tool is a thin wrapper around this crate,
+//! which can also be used directly:
+//!
+//! ```
+//! use five_three_one::config::parse_training_maxes_from_str;
+//! use five_three_one::lifts::{generate_primary_sets, Lift, PrimaryVariant, Week};
+//! use five_three_one::rounding::RoundingRules;
+//! use five_three_one::scheme::Scheme;
+//!
+//! let config = "[default]
+//! squat = 325
+//! bench_press = 235
+//! deadlift = 365
+//! overhead_press = 170
+//! ";
+//! let training_maxes = parse_training_maxes_from_str(config, "training_max.toml").unwrap();
+//! let sets = generate_primary_sets(
+//!     &Lift::Squat,
+//!     &Week::Week1,
+//!     &PrimaryVariant::Standard,
+//!     0,
+//!     &Scheme::default(),
+//!     &RoundingRules::default(),
+//!     &training_maxes,
+//! )
+//! .unwrap();
+//! let top_set = sets.last().unwrap();
+//! assert_eq!((top_set.weight, top_set.reps, top_set.amrap), (276.0, 5, true));
+//! ```
+//!
+//! - [`lifts`]: the lift catalog and the set generators for each part of a session
+//! - [`config`]: parsing of the TOML config file
+//! - [`session`]: a whole session's [`workout::Workout`] with its optional parts
+//! - [`text`], [`json`], [`markdown`], [`csv`] and [`html`]: renderers for workouts
+//! - [`schedule`] and [`block`]: how sessions are laid out over weeks and cycles
+//! - [`training_log`] and [`e1rm`]: the training log and estimated one-rep maxes
+
+#![warn(missing_docs)]
+
+pub mod block;
+pub mod config;
+pub mod csv;
+pub mod e1rm;
+pub mod format;
+pub mod html;
+pub mod json;
+pub mod lifts;
+pub mod markdown;
+pub mod plates;
+pub mod rounding;
+pub mod schedule;
+pub mod scheme;
+pub mod session;
+pub mod static_strings;
+pub mod text;
+pub mod training_log;
+pub mod units;
+pub mod warm_up;
+pub mod workout;
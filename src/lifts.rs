@@ -1,25 +1,108 @@
+//! The lift catalog and the generators for each part of a session: primary
+//! lift sets, supplemental sets, assistance lifts and TM tests.
+
 use rand::Rng;
 use std::collections::HashMap;
 use std::fmt;
+use std::str::FromStr;
 use strum_macros::EnumString;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+use crate::rounding::{Rounding, RoundingRules};
+use crate::scheme::{Scheme, SetScheme, WeekScheme};
+use crate::workout::Exercise;
+
+/// Week of a 5/3/1 cycle
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
 pub enum Week {
+    /// Sets of 5, working up to an AMRAP set at 85% in the default scheme
     Week1,
+    /// Sets of 3, working up to an AMRAP set at 90% in the default scheme
     Week2,
+    /// 5/3/1 week, working up to an AMRAP single at 95% in the default scheme
     Week3,
+    /// Deload
     Week4,
+    /// 7th week after the Leader or Anchor cycles of a block
+    Week7(SeventhWeek),
 }
 
+/// 5/3/1 Forever 7th week protocol
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, EnumString)]
+pub enum SeventhWeek {
+    /// 70/80/90/100% for 5, 3-5, 1, 1
+    #[strum(serialize = "deload")]
+    Deload,
+    /// 70/80/90/100% for 5; the 100% set validates the training max
+    #[strum(serialize = "tm-test", serialize = "tm_test", serialize = "test")]
+    TmTest,
+}
+
+impl SeventhWeek {
+    /// Name as written on the CLI and in output files, e.g. "tm-test"
+    pub fn name(&self) -> &'static str {
+        match self {
+            SeventhWeek::Deload => "deload",
+            SeventhWeek::TmTest => "tm-test",
+        }
+    }
+}
+
+impl fmt::Display for SeventhWeek {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s: &str = match self {
+            SeventhWeek::Deload => "deload",
+            SeventhWeek::TmTest => "TM test",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+impl Week {
+    /// Week number as written on the CLI
+    pub fn number(&self) -> u8 {
+        match self {
+            Week::Week1 => 1,
+            Week::Week2 => 2,
+            Week::Week3 => 3,
+            Week::Week4 => 4,
+            Week::Week7(_) => 7,
+        }
+    }
+
+    /// 7th week protocol, or `None` for weeks 1-4
+    pub fn seventh_week(&self) -> Option<SeventhWeek> {
+        match self {
+            Week::Week7(protocol) => Some(*protocol),
+            _ => None,
+        }
+    }
+
+    /// Whether this is a light week with no supplemental volume.
+    pub fn is_deload(&self) -> bool {
+        matches!(self, Week::Week4 | Week::Week7(_))
+    }
+}
+
+/// A primary lift, or an assistance lift that's programmed from its own
+/// training max
 #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, EnumString)]
 pub enum Lift {
     /* Primary */
+    /// Primary lift
     #[strum(serialize = "squat", serialize = "s")]
     Squat,
-    #[strum(serialize = "bench_press", serialize = "bench-press", serialize = "b", serialize = "bp")]
+    /// Primary lift
+    #[strum(
+        serialize = "bench_press",
+        serialize = "bench-press",
+        serialize = "b",
+        serialize = "bp"
+    )]
     BenchPress,
+    /// Primary lift
     #[strum(serialize = "deadlift", serialize = "d", serialize = "dl")]
     Deadlift,
+    /// Primary lift
     #[strum(
         serialize = "overhead_press",
         serialize = "overhead-press",
@@ -31,41 +114,107 @@ pub enum Lift {
 
     /* Major assistance */
     // squat-like
+    /// Squat-like assistance
     #[strum(serialize = "front_squat", serialize = "fs")]
     FrontSquat,
+    /// Squat-like assistance
     #[strum(serialize = "overhead_squat", serialize = "os", serialize = "ohs")]
     OverheadSquat,
+    /// Squat-like assistance
     #[strum(serialize = "bulgarian_split_squat", serialize = "bss")]
     BulgarianSplitSquat,
     // deadlift-like
+    /// Deadlift-like assistance
     #[strum(serialize = "good_morning", serialize = "gm")]
     GoodMorning,
+    /// Deadlift-like assistance
     #[strum(serialize = "straight_leg_deadlift", serialize = "sldl")]
     StraightLegDeadlift,
+    /// Deadlift-like assistance
     #[strum(serialize = "romanian_deadlift", serialize = "rdl")]
     RomanianDeadlift,
+    /// Deadlift-like assistance
     #[strum(serialize = "rack_deadlift", serialize = "radl")]
     RackDeadlift,
+    /// Deadlift-like assistance
     #[strum(serialize = "power_clean", serialize = "pc")]
     PowerClean,
+    /// Deadlift-like assistance
     #[strum(serialize = "power_snatch", serialize = "ps")]
     PowerSnatch,
     // bench press-like
+    /// Bench press-like assistance
     #[strum(serialize = "close_grip_bench_press", serialize = "cgbp")]
     CloseGripBenchPress,
     // overhead press-like
+    /// Overhead press-like assistance
     #[strum(serialize = "incline_press", serialize = "ip")]
     InclinePress,
 }
 
 impl Lift {
-    pub const PRIMARY_LIFTS: [Lift; 4] = [Lift::Squat, Lift::BenchPress, Lift::Deadlift, Lift::OverheadPress];
+    /// The lifts a session can be built around
+    pub const PRIMARY_LIFTS: [Lift; 4] = [
+        Lift::Squat,
+        Lift::BenchPress,
+        Lift::Deadlift,
+        Lift::OverheadPress,
+    ];
+
+    /// The lift's key in the config file, e.g. "bench_press", used wherever a
+    /// lift is written for other programs to read back
+    pub fn config_key(&self) -> &'static str {
+        match self {
+            Lift::Squat => "squat",
+            Lift::BenchPress => "bench_press",
+            Lift::Deadlift => "deadlift",
+            Lift::OverheadPress => "overhead_press",
+            Lift::FrontSquat => "front_squat",
+            Lift::OverheadSquat => "overhead_squat",
+            Lift::BulgarianSplitSquat => "bulgarian_split_squat",
+            Lift::GoodMorning => "good_morning",
+            Lift::StraightLegDeadlift => "straight_leg_deadlift",
+            Lift::RomanianDeadlift => "romanian_deadlift",
+            Lift::RackDeadlift => "rack_deadlift",
+            Lift::PowerClean => "power_clean",
+            Lift::PowerSnatch => "power_snatch",
+            Lift::CloseGripBenchPress => "close_grip_bench_press",
+            Lift::InclinePress => "incline_press",
+        }
+    }
+
+    /// Big assistance lift paired with a primary lift, or `None` for non-primary lifts
+    pub fn big_assistance(&self) -> Option<Lift> {
+        match self {
+            Lift::Squat => Some(Lift::PowerClean),
+            Lift::Deadlift => Some(Lift::FrontSquat),
+            Lift::BenchPress => Some(Lift::InclinePress),
+            Lift::OverheadPress => Some(Lift::CloseGripBenchPress),
+            _ => None,
+        }
+    }
+
+    /// Whether the lift is trained with the upper body, which progresses in
+    /// smaller increments than the lower body
+    pub fn is_upper_body(&self) -> bool {
+        matches!(
+            self,
+            Lift::BenchPress | Lift::OverheadPress | Lift::CloseGripBenchPress | Lift::InclinePress
+        )
+    }
 }
 
+/// Error generating a workout
 #[derive(Debug, PartialEq, Eq)]
 pub enum WorkoutError {
+    /// The config file, the training log or the options given are invalid; the
+    /// message says how
     Config(String),
-    MissingTrainingMax { lift: Lift },
+    /// A lift needed for the session has no training max
+    MissingTrainingMax {
+        /// The lift without a training max
+        lift: Lift,
+    },
 }
 
 impl fmt::Display for WorkoutError {
@@ -105,18 +254,33 @@ impl fmt::Display for Lift {
 }
 
 /// A block of identical sets for a lift
+#[derive(Debug, Clone, PartialEq)]
 pub struct SetGroup {
-    lift: Lift,
-    weight: i16,
-    sets: i8,
-    reps: i8,
-    amrap: bool,
+    /// Lift the sets are done with
+    pub lift: Lift,
+    /// Weight in the config file's unit
+    pub weight: f32,
+    /// Fraction of the lift's training max the weight was prescribed at; `None`
+    /// for an empty bar
+    pub scalar: Option<f32>,
+    /// Number of sets
+    pub sets: i8,
+    /// Reps per set, or the least reps for a rep range
+    pub reps: i8,
+    /// Upper end of a rep range such as "x3-5"
+    pub max_reps: Option<i8>,
+    /// Whether the last set is done for as many reps as possible, e.g. "x5+"
+    pub amrap: bool,
+    /// Shown in parentheses after the set, e.g. "joker, optional"
+    pub note: Option<&'static str>,
 }
 
-impl fmt::Display for SetGroup {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+impl SetGroup {
+    /// Formats the set with `weight` written in place of its weight, e.g. a
+    /// weight converted to another unit.
+    pub fn to_string_with_weight(&self, weight: &str) -> String {
         // e.g. "squat 315 "
-        let mut s = self.lift.to_string() + " " + &self.weight.to_string() + " ";
+        let mut s = self.lift.to_string() + " " + weight + " ";
 
         // "...3"?
         if self.sets > 1 {
@@ -127,75 +291,260 @@ impl fmt::Display for SetGroup {
         s += "x";
         s += &self.reps.to_string();
 
+        // "...-5"?
+        if let Some(max_reps) = self.max_reps {
+            s += "-";
+            s += &max_reps.to_string();
+        }
+
         // "...+"
         if self.amrap {
             s += "+";
         }
 
-        write!(f, "{}", s)
+        // "... (joker, optional)"?
+        if let Some(note) = self.note {
+            s += " (";
+            s += note;
+            s += ")";
+        }
+
+        s
+    }
+}
+
+impl fmt::Display for SetGroup {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            self.to_string_with_weight(&self.weight.to_string())
+        )
     }
 }
 
-/// Scales integer weight by floating point multiplier and converts back to integer weight.
-pub fn scale(weight: i16, scale: f32) -> i16 {
-    return (weight as f32 * scale).round() as i16;
+/// Sets the scheme gives for `week`, or an error if it doesn't define that week.
+fn week_scheme<'a>(scheme: &'a Scheme, week: &Week) -> Result<&'a WeekScheme, WorkoutError> {
+    scheme.week(week).ok_or_else(|| {
+        let protocol = week
+            .seventh_week()
+            .map_or(String::new(), |protocol| format!(" ({})", protocol.name()));
+        WorkoutError::Config(format!(
+            "Scheme has no sets for week {}{}",
+            week.number(),
+            protocol
+        ))
+    })
+}
+
+/// Scales weight by a multiplier and rounds back to a loadable weight.
+pub fn scale(weight: f32, scale: f32, rounding: &Rounding) -> f32 {
+    rounding.round(weight * scale)
+}
+
+/// Reps that must be completed on the TM test set for the training max to pass
+pub const TM_TEST_REPS: i8 = 5;
+
+/// Training max fraction to reset to after a failed TM test
+const TM_RESET_SCALAR: f32 = 0.9;
+
+/// Outcome of the 7th week TM test
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TmTestResult {
+    /// The training max is validated and can be kept
+    Pass,
+    /// The training max is too heavy and should be reset
+    Fail {
+        /// 90% of the training max, rounded
+        reset_to: f32,
+    },
+}
+
+impl fmt::Display for TmTestResult {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TmTestResult::Pass => write!(f, "pass, keep training max"),
+            TmTestResult::Fail { reset_to } => {
+                write!(f, "fail, reset training max to {}", reset_to)
+            }
+        }
+    }
+}
+
+/// Checks the reps completed on the TM test set against the lift's training max.
+pub fn evaluate_tm_test(
+    lift: &Lift,
+    reps: i8,
+    rounding: &RoundingRules,
+    training_maxes: &HashMap<Lift, f32>,
+) -> Result<TmTestResult, WorkoutError> {
+    let training_max = *training_maxes
+        .get(lift)
+        .ok_or(WorkoutError::MissingTrainingMax { lift: *lift })?;
+
+    if reps >= TM_TEST_REPS {
+        Ok(TmTestResult::Pass)
+    } else {
+        Ok(TmTestResult::Fail {
+            reset_to: scale(training_max, TM_RESET_SCALAR, rounding.for_lift(lift)),
+        })
+    }
+}
+
+/// How the primary lift's working sets are performed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumString)]
+pub enum PrimaryVariant {
+    /// 5/3/1 rep counts with an AMRAP top set
+    #[strum(serialize = "standard", serialize = "531")]
+    Standard,
+    /// Straight sets of 5 at the week's percentages, no AMRAP
+    #[strum(serialize = "5s-pro", serialize = "5s_pro", serialize = "fives-pro")]
+    FivesPro,
+    /// 5/3/1 rep counts, then back down through the second and first working
+    /// set weights with the last down set as AMRAP
+    #[strum(
+        serialize = "pyramid",
+        serialize = "pr-set-down",
+        serialize = "pr_set_down"
+    )]
+    Pyramid,
+}
+
+impl fmt::Display for PrimaryVariant {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s: &str = match self {
+            PrimaryVariant::Standard => "5/3/1",
+            PrimaryVariant::FivesPro => "5s PRO",
+            PrimaryVariant::Pyramid => "pyramid",
+        };
+        write!(f, "{}", s)
+    }
 }
 
-/// Primary lift set generator
+/// Maximum number of Joker sets that can be requested
+pub const MAX_JOKERS: u8 = 3;
+
+/// Primary lift set generator: the warm-up ramp, working sets and any extra
+/// sets of the variant, one `SetGroup` per set
+///
+/// `jokers` optional heavier sets are added after the AMRAP top set in the 3s and
+/// 1s weeks, each the scheme's Joker increment (5% of training max by default)
+/// above the previous set. Asking for them in
+/// any other week, or with a variant other than 5/3/1, is an error.
 pub fn generate_primary_sets(
     lift: &Lift,
     week: &Week,
-    training_maxes: &HashMap<Lift, i16>,
-) -> Result<Vec<String>, WorkoutError> {
+    variant: &PrimaryVariant,
+    jokers: u8,
+    scheme: &Scheme,
+    rounding: &RoundingRules,
+    training_maxes: &HashMap<Lift, f32>,
+) -> Result<Vec<SetGroup>, WorkoutError> {
     let mut ret = vec![];
     let training_max = *training_maxes
         .get(lift)
         .ok_or(WorkoutError::MissingTrainingMax { lift: *lift })?;
 
-    let make_set_str = |scalar: f32, sets: i8, reps: i8, amrap: bool| -> String {
+    let rounding = rounding.for_lift(lift);
+    let make_set = |set: &SetScheme| -> SetGroup {
         SetGroup {
             lift: *lift,
-            weight: scale(training_max, scalar),
-            sets,
-            reps,
-            amrap,
+            weight: scale(training_max, set.scalar, rounding),
+            scalar: Some(set.scalar),
+            sets: 1,
+            reps: set.reps,
+            max_reps: set.max_reps,
+            amrap: set.amrap,
+            note: None,
         }
-        .to_string()
     };
 
-    // warm-up sets
-    // no warm-up needed for deload week
-    if week != &Week::Week4 {
-        ret.push(make_set_str(0.4, 1, 5, false));
-        ret.push(make_set_str(0.5, 1, 5, false));
-        // for week 1, the 60% warm-up is too close to the first working set at
-        // 65% to be helpful
-        if week != &Week::Week1 {
-            ret.push(make_set_str(0.6, 1, 3, false));
+    let week_scheme = week_scheme(scheme, week)?;
+
+    // warm-up sets, from the week if it gives its own or else the lift's ramp
+    match &week_scheme.warm_up {
+        Some(warm_up) => ret.extend(warm_up.iter().map(make_set)),
+        None => {
+            let warm_up = scheme.warm_up(lift);
+            let first_working = week_scheme.working.first().map_or(1.0, |set| set.scalar);
+            let first_working_weight = scale(training_max, first_working, rounding);
+            if let Some(reps) = warm_up.empty_bar_before(first_working_weight, training_max) {
+                ret.push(SetGroup {
+                    lift: *lift,
+                    weight: warm_up.bar,
+                    scalar: None,
+                    sets: 1,
+                    reps,
+                    max_reps: None,
+                    amrap: false,
+                    note: Some("empty bar"),
+                });
+            }
+            ret.extend(warm_up.sets_before(first_working).iter().map(make_set));
         }
     }
 
     // working sets
-    match week {
-        Week::Week1 => {
-            ret.push(make_set_str(0.65, 1, 5, false));
-            ret.push(make_set_str(0.75, 1, 5, false));
-            ret.push(make_set_str(0.85, 1, 5, true));
+    let sets = &week_scheme.working;
+    for set in sets.iter() {
+        match (variant, week) {
+            // deload and 7th weeks are the same for every variant
+            (_, Week::Week4 | Week::Week7(_)) | (PrimaryVariant::Standard, _) => {
+                ret.push(make_set(set));
+            }
+            (PrimaryVariant::FivesPro, _) => {
+                ret.push(make_set(&SetScheme::new(set.scalar, 5, false)))
+            }
+            // the AMRAP moves to the last down set
+            (PrimaryVariant::Pyramid, _) => ret.push(make_set(&SetScheme {
+                amrap: false,
+                ..*set
+            })),
         }
-        Week::Week2 => {
-            ret.push(make_set_str(0.7, 1, 3, false));
-            ret.push(make_set_str(0.8, 1, 3, false));
-            ret.push(make_set_str(0.9, 1, 3, true));
+    }
+
+    // down sets, back through every working set below the top set
+    if variant == &PrimaryVariant::Pyramid && !week.is_deload() {
+        let down_sets = &sets[..sets.len().saturating_sub(1)];
+        for (i, set) in down_sets.iter().enumerate().rev() {
+            ret.push(make_set(&SetScheme {
+                amrap: i == 0,
+                ..*set
+            }));
         }
-        Week::Week3 => {
-            ret.push(make_set_str(0.75, 1, 5, false));
-            ret.push(make_set_str(0.85, 1, 3, false));
-            ret.push(make_set_str(0.95, 1, 1, true));
+    }
+
+    // the last set of the TM test week validates the training max
+    if week == &Week::Week7(SeventhWeek::TmTest) {
+        if let Some(last) = ret.last_mut() {
+            last.note = Some("TM test");
         }
-        Week::Week4 => {
-            ret.push(make_set_str(0.4, 1, 5, false));
-            ret.push(make_set_str(0.5, 1, 5, false));
-            ret.push(make_set_str(0.6, 1, 5, false));
+    }
+
+    // Joker sets, only after a standard AMRAP top set of triples or singles
+    if jokers > 0 {
+        let (reps, top_set) = match (variant, scheme.joker_reps(week), sets.last()) {
+            (PrimaryVariant::Standard, Some(reps), Some(top_set)) => (reps, top_set),
+            (PrimaryVariant::Standard, ..) => {
+                return Err(WorkoutError::Config(format!(
+                    "Joker sets need an AMRAP top set of 3 or fewer reps, which {} week {} doesn't have",
+                    lift,
+                    week.number()
+                )))
+            }
+            _ => {
+                return Err(WorkoutError::Config(format!(
+                    "Joker sets are only done with the 5/3/1 variant, not {}",
+                    variant
+                )))
+            }
+        };
+        for i in 1..=jokers.min(MAX_JOKERS) {
+            let scalar = top_set.scalar + scheme.joker_increment() * i as f32;
+            ret.push(SetGroup {
+                note: Some("joker, optional"),
+                ..make_set(&SetScheme::new(scalar, reps, false))
+            });
         }
     }
 
@@ -205,27 +554,66 @@ pub fn generate_primary_sets(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::rounding::RoundingMode;
     use rand::rngs::StdRng;
     use rand::SeedableRng;
     use std::collections::HashMap;
 
-    fn baseline_training_maxes() -> HashMap<Lift, i16> {
+    fn to_strings(items: &[impl ToString]) -> Vec<String> {
+        items.iter().map(|item| item.to_string()).collect()
+    }
+
+    fn baseline_training_maxes() -> HashMap<Lift, f32> {
         let mut map = HashMap::new();
-        map.insert(Lift::Squat, 325);
-        map.insert(Lift::BenchPress, 235);
-        map.insert(Lift::Deadlift, 365);
-        map.insert(Lift::OverheadPress, 170);
-        map.insert(Lift::PowerClean, 205);
-        map.insert(Lift::FrontSquat, 215);
-        map.insert(Lift::InclinePress, 215);
-        map.insert(Lift::CloseGripBenchPress, 215);
+        map.insert(Lift::Squat, 325.0);
+        map.insert(Lift::BenchPress, 235.0);
+        map.insert(Lift::Deadlift, 365.0);
+        map.insert(Lift::OverheadPress, 170.0);
+        map.insert(Lift::PowerClean, 205.0);
+        map.insert(Lift::FrontSquat, 215.0);
+        map.insert(Lift::InclinePress, 215.0);
+        map.insert(Lift::CloseGripBenchPress, 215.0);
         map
     }
 
+    #[test]
+    fn config_keys_are_config_file_names() {
+        let lifts = [
+            Lift::BenchPress,
+            Lift::OverheadPress,
+            Lift::PowerClean,
+            Lift::CloseGripBenchPress,
+        ];
+        let keys: Vec<&str> = lifts.iter().map(|lift| lift.config_key()).collect();
+        assert_eq!(
+            keys,
+            vec![
+                "bench_press",
+                "overhead_press",
+                "power_clean",
+                "close_grip_bench_press"
+            ]
+        );
+        for lift in lifts {
+            assert_eq!(Lift::from_str(lift.config_key()), Ok(lift));
+        }
+    }
+
     #[test]
     fn generates_expected_week_one_primary_sets() {
         let training_maxes = baseline_training_maxes();
-        let sets = generate_primary_sets(&Lift::Squat, &Week::Week1, &training_maxes).unwrap();
+        let sets = to_strings(
+            &generate_primary_sets(
+                &Lift::Squat,
+                &Week::Week1,
+                &PrimaryVariant::Standard,
+                0,
+                &Scheme::default(),
+                &RoundingRules::default(),
+                &training_maxes,
+            )
+            .unwrap(),
+        );
         assert_eq!(
             sets,
             vec![
@@ -241,7 +629,18 @@ mod tests {
     #[test]
     fn generates_expected_week_three_primary_sets() {
         let training_maxes = baseline_training_maxes();
-        let sets = generate_primary_sets(&Lift::Squat, &Week::Week3, &training_maxes).unwrap();
+        let sets = to_strings(
+            &generate_primary_sets(
+                &Lift::Squat,
+                &Week::Week3,
+                &PrimaryVariant::Standard,
+                0,
+                &Scheme::default(),
+                &RoundingRules::default(),
+                &training_maxes,
+            )
+            .unwrap(),
+        );
         assert_eq!(
             sets,
             vec![
@@ -258,24 +657,127 @@ mod tests {
     #[test]
     fn generates_expected_week_four_primary_sets() {
         let training_maxes = baseline_training_maxes();
-        let sets = generate_primary_sets(&Lift::Squat, &Week::Week4, &training_maxes).unwrap();
+        let sets = to_strings(
+            &generate_primary_sets(
+                &Lift::Squat,
+                &Week::Week4,
+                &PrimaryVariant::Standard,
+                0,
+                &Scheme::default(),
+                &RoundingRules::default(),
+                &training_maxes,
+            )
+            .unwrap(),
+        );
+        assert_eq!(sets, vec!["squat 130 x5", "squat 163 x5", "squat 195 x5",]);
+    }
+
+    #[test]
+    fn primary_sets_follow_the_given_scheme() {
+        let training_maxes = baseline_training_maxes();
+        let sets = to_strings(
+            &generate_primary_sets(
+                &Lift::Squat,
+                &Week::Week1,
+                &PrimaryVariant::Standard,
+                1,
+                &Scheme::three_five_one(),
+                &RoundingRules::default(),
+                &training_maxes,
+            )
+            .unwrap(),
+        );
         assert_eq!(
-            sets,
+            &sets[3..],
+            &[
+                "squat 228 x3",
+                "squat 260 x3",
+                "squat 293 x3+",
+                "squat 309 x3 (joker, optional)",
+            ]
+        );
+    }
+
+    #[test]
+    fn generators_round_each_lift_to_its_increment() {
+        let training_maxes = baseline_training_maxes();
+        let mut rounding = RoundingRules {
+            default: Rounding {
+                increment: 5.0,
+                mode: RoundingMode::Nearest,
+            },
+            ..Default::default()
+        };
+        rounding.lifts.insert(
+            Lift::PowerClean,
+            Rounding {
+                increment: 10.0,
+                mode: RoundingMode::Down,
+            },
+        );
+        let primary = to_strings(
+            &generate_primary_sets(
+                &Lift::Squat,
+                &Week::Week1,
+                &PrimaryVariant::Standard,
+                0,
+                &Scheme::default(),
+                &rounding,
+                &training_maxes,
+            )
+            .unwrap(),
+        );
+        assert_eq!(
+            primary,
             vec![
                 "squat 130 x5",
-                "squat 163 x5",
-                "squat 195 x5",
+                "squat 165 x5",
+                "squat 210 x5",
+                "squat 245 x5",
+                "squat 275 x5+",
             ]
         );
+        let bbb = to_strings(
+            &generate_supplemental_sets(
+                &Lift::Squat,
+                &Week::Week1,
+                &Supplemental::BoringButBig(BbbPercent::Sixty),
+                &Scheme::default(),
+                &rounding,
+                &training_maxes,
+            )
+            .unwrap(),
+        );
+        assert_eq!(bbb, vec!["squat 195 5x10"]);
+        let assistance = generate_big_assistance_sets(
+            &Lift::Squat,
+            &Week::Week1,
+            &Scheme::default(),
+            &rounding,
+            &training_maxes,
+        )
+        .unwrap();
+        let weights: Vec<f32> = assistance
+            .iter()
+            .map(|set_group| set_group.weight)
+            .collect();
+        assert_eq!(weights, vec![130.0, 150.0, 170.0]);
     }
 
     #[test]
     fn assistance_sets_require_training_max() {
         let mut training_maxes = HashMap::new();
-        training_maxes.insert(Lift::Squat, 325);
+        training_maxes.insert(Lift::Squat, 325.0);
         let mut rng = StdRng::seed_from_u64(1);
-        let err = generate_assistance_sets(&Lift::Squat, &Week::Week1, &training_maxes, &mut rng)
-            .unwrap_err();
+        let err = generate_assistance_sets(
+            &Lift::Squat,
+            &Week::Week1,
+            &Scheme::default(),
+            &RoundingRules::default(),
+            &training_maxes,
+            &mut rng,
+        )
+        .unwrap_err();
         assert_eq!(
             err,
             WorkoutError::MissingTrainingMax {
@@ -289,12 +791,28 @@ mod tests {
         let training_maxes = baseline_training_maxes();
         let mut rng_a = StdRng::seed_from_u64(42);
         let mut rng_b = StdRng::seed_from_u64(42);
-        let sets_a =
-            generate_assistance_sets(&Lift::BenchPress, &Week::Week2, &training_maxes, &mut rng_a)
-                .unwrap();
-        let sets_b =
-            generate_assistance_sets(&Lift::BenchPress, &Week::Week2, &training_maxes, &mut rng_b)
-                .unwrap();
+        let sets_a = to_strings(
+            &generate_assistance_sets(
+                &Lift::BenchPress,
+                &Week::Week2,
+                &Scheme::default(),
+                &RoundingRules::default(),
+                &training_maxes,
+                &mut rng_a,
+            )
+            .unwrap(),
+        );
+        let sets_b = to_strings(
+            &generate_assistance_sets(
+                &Lift::BenchPress,
+                &Week::Week2,
+                &Scheme::default(),
+                &RoundingRules::default(),
+                &training_maxes,
+                &mut rng_b,
+            )
+            .unwrap(),
+        );
         assert_eq!(sets_a, sets_b);
     }
 
@@ -302,8 +820,17 @@ mod tests {
     fn squat_assistance_matches_expected_scales_and_shape() {
         let training_maxes = baseline_training_maxes();
         let mut rng = StdRng::seed_from_u64(0);
-        let sets = generate_assistance_sets(&Lift::Squat, &Week::Week2, &training_maxes, &mut rng)
-            .unwrap();
+        let sets = to_strings(
+            &generate_assistance_sets(
+                &Lift::Squat,
+                &Week::Week2,
+                &Scheme::default(),
+                &RoundingRules::default(),
+                &training_maxes,
+                &mut rng,
+            )
+            .unwrap(),
+        );
         assert_eq!(
             &sets[0..3],
             &[
@@ -316,77 +843,691 @@ mod tests {
         assert!(sets[4] == "pull-ups, 2x10" || sets[4] == "chin-ups, 2x10");
         assert_eq!(sets.len(), 5);
     }
+
+    #[test]
+    fn boring_but_big_emits_five_sets_of_ten() {
+        let training_maxes = baseline_training_maxes();
+        let sets = to_strings(
+            &generate_supplemental_sets(
+                &Lift::Squat,
+                &Week::Week1,
+                &Supplemental::BoringButBig(BbbPercent::Sixty),
+                &Scheme::default(),
+                &RoundingRules::default(),
+                &training_maxes,
+            )
+            .unwrap(),
+        );
+        assert_eq!(sets, vec!["squat 195 5x10"]);
+    }
+
+    #[test]
+    fn boring_but_big_week_dependent_percentages() {
+        let training_maxes = baseline_training_maxes();
+        let bbb = Supplemental::BoringButBig(BbbPercent::Ascending);
+        let week1 = to_strings(
+            &generate_supplemental_sets(
+                &Lift::BenchPress,
+                &Week::Week1,
+                &bbb,
+                &Scheme::default(),
+                &RoundingRules::default(),
+                &training_maxes,
+            )
+            .unwrap(),
+        );
+        let week3 = to_strings(
+            &generate_supplemental_sets(
+                &Lift::BenchPress,
+                &Week::Week3,
+                &bbb,
+                &Scheme::default(),
+                &RoundingRules::default(),
+                &training_maxes,
+            )
+            .unwrap(),
+        );
+        let week4 = to_strings(
+            &generate_supplemental_sets(
+                &Lift::BenchPress,
+                &Week::Week4,
+                &bbb,
+                &Scheme::default(),
+                &RoundingRules::default(),
+                &training_maxes,
+            )
+            .unwrap(),
+        );
+        assert_eq!(week1, vec!["bench press 118 5x10"]);
+        assert_eq!(week3, vec!["bench press 165 5x10"]);
+        assert!(week4.is_empty());
+    }
+
+    #[test]
+    fn first_and_second_set_last_use_working_set_weights() {
+        let training_maxes = baseline_training_maxes();
+        let scheme = SetsReps::from_str("5x5").unwrap();
+        let fsl = to_strings(
+            &generate_supplemental_sets(
+                &Lift::Squat,
+                &Week::Week2,
+                &Supplemental::FirstSetLast(scheme),
+                &Scheme::default(),
+                &RoundingRules::default(),
+                &training_maxes,
+            )
+            .unwrap(),
+        );
+        let ssl = to_strings(
+            &generate_supplemental_sets(
+                &Lift::Squat,
+                &Week::Week2,
+                &Supplemental::SecondSetLast(SetsReps { sets: 3, reps: 8 }),
+                &Scheme::default(),
+                &RoundingRules::default(),
+                &training_maxes,
+            )
+            .unwrap(),
+        );
+        assert_eq!(fsl, vec!["squat 228 5x5"]);
+        assert_eq!(ssl, vec!["squat 260 3x8"]);
+    }
+
+    #[test]
+    fn sets_reps_parses_and_rejects_schemes() {
+        assert_eq!(SetsReps::from_str("3x8"), Ok(SetsReps { sets: 3, reps: 8 }));
+        assert!(SetsReps::from_str("5").is_err());
+        assert!(SetsReps::from_str("0x5").is_err());
+        assert!(SetsReps::from_str("fivexfive").is_err());
+    }
+
+    #[test]
+    fn fives_pro_replaces_amrap_with_straight_fives() {
+        let training_maxes = baseline_training_maxes();
+        let sets = to_strings(
+            &generate_primary_sets(
+                &Lift::Deadlift,
+                &Week::Week3,
+                &PrimaryVariant::FivesPro,
+                0,
+                &Scheme::default(),
+                &RoundingRules::default(),
+                &training_maxes,
+            )
+            .unwrap(),
+        );
+        assert_eq!(
+            &sets[3..],
+            &["deadlift 274 x5", "deadlift 310 x5", "deadlift 347 x5"]
+        );
+    }
+
+    #[test]
+    fn jokers_ladder_above_top_set_by_week() {
+        let training_maxes = baseline_training_maxes();
+        let sets = to_strings(
+            &generate_primary_sets(
+                &Lift::Squat,
+                &Week::Week3,
+                &PrimaryVariant::Standard,
+                2,
+                &Scheme::default(),
+                &RoundingRules::default(),
+                &training_maxes,
+            )
+            .unwrap(),
+        );
+        assert_eq!(
+            &sets[5..],
+            &[
+                "squat 309 x1+",
+                "squat 325 x1 (joker, optional)",
+                "squat 341 x1 (joker, optional)",
+            ]
+        );
+
+        let sets = to_strings(
+            &generate_primary_sets(
+                &Lift::Squat,
+                &Week::Week2,
+                &PrimaryVariant::Standard,
+                5,
+                &Scheme::default(),
+                &RoundingRules::default(),
+                &training_maxes,
+            )
+            .unwrap(),
+        );
+        assert_eq!(sets.len(), 6 + MAX_JOKERS as usize);
+        assert_eq!(sets[6], "squat 309 x3 (joker, optional)");
+
+        let mut scheme = Scheme::default();
+        scheme.set_joker_increment(0.1);
+        let sets = to_strings(
+            &generate_primary_sets(
+                &Lift::Squat,
+                &Week::Week3,
+                &PrimaryVariant::Standard,
+                2,
+                &scheme,
+                &RoundingRules::default(),
+                &training_maxes,
+            )
+            .unwrap(),
+        );
+        assert_eq!(
+            &sets[6..],
+            &[
+                "squat 341 x1 (joker, optional)",
+                "squat 374 x1 (joker, optional)",
+            ]
+        );
+    }
+
+    #[test]
+    fn jokers_are_rejected_where_they_cannot_be_done() {
+        let training_maxes = baseline_training_maxes();
+        let jokers = |week: Week, variant: PrimaryVariant| {
+            generate_primary_sets(
+                &Lift::Squat,
+                &week,
+                &variant,
+                2,
+                &Scheme::default(),
+                &RoundingRules::default(),
+                &training_maxes,
+            )
+            .unwrap_err()
+            .to_string()
+        };
+        let no_top_set = "Joker sets need an AMRAP top set of 3 or fewer reps, which squat week";
+        assert_eq!(
+            jokers(Week::Week1, PrimaryVariant::Standard),
+            format!("{} 1 doesn't have", no_top_set)
+        );
+        assert_eq!(
+            jokers(Week::Week4, PrimaryVariant::Standard),
+            format!("{} 4 doesn't have", no_top_set)
+        );
+        assert_eq!(
+            jokers(Week::Week7(SeventhWeek::TmTest), PrimaryVariant::Standard),
+            format!("{} 7 doesn't have", no_top_set)
+        );
+        assert_eq!(
+            jokers(Week::Week3, PrimaryVariant::Pyramid),
+            "Joker sets are only done with the 5/3/1 variant, not pyramid"
+        );
+        assert_eq!(
+            jokers(Week::Week2, PrimaryVariant::FivesPro),
+            "Joker sets are only done with the 5/3/1 variant, not 5s PRO"
+        );
+    }
+
+    #[test]
+    fn pyramid_descends_with_amrap_last_down_set() {
+        let training_maxes = baseline_training_maxes();
+        let sets = to_strings(
+            &generate_primary_sets(
+                &Lift::Squat,
+                &Week::Week3,
+                &PrimaryVariant::Pyramid,
+                0,
+                &Scheme::default(),
+                &RoundingRules::default(),
+                &training_maxes,
+            )
+            .unwrap(),
+        );
+        assert_eq!(
+            &sets[3..],
+            &[
+                "squat 244 x5",
+                "squat 276 x3",
+                "squat 309 x1",
+                "squat 276 x3",
+                "squat 244 x5+",
+            ]
+        );
+
+        let deload = to_strings(
+            &generate_primary_sets(
+                &Lift::Squat,
+                &Week::Week4,
+                &PrimaryVariant::Pyramid,
+                0,
+                &Scheme::default(),
+                &RoundingRules::default(),
+                &training_maxes,
+            )
+            .unwrap(),
+        );
+        assert_eq!(deload.len(), 3);
+    }
+
+    #[test]
+    fn widowmaker_uses_primary_or_big_assistance_training_max() {
+        let training_maxes = baseline_training_maxes();
+        let primary = to_strings(
+            &generate_supplemental_sets(
+                &Lift::Squat,
+                &Week::Week1,
+                &Supplemental::Widowmaker {
+                    lift: WidowmakerLift::Primary,
+                    percent: 60,
+                    reps: 20,
+                },
+                &Scheme::default(),
+                &RoundingRules::default(),
+                &training_maxes,
+            )
+            .unwrap(),
+        );
+        let assistance = to_strings(
+            &generate_supplemental_sets(
+                &Lift::Deadlift,
+                &Week::Week2,
+                &Supplemental::Widowmaker {
+                    lift: WidowmakerLift::BigAssistance,
+                    percent: 50,
+                    reps: 20,
+                },
+                &Scheme::default(),
+                &RoundingRules::default(),
+                &training_maxes,
+            )
+            .unwrap(),
+        );
+        assert_eq!(primary, vec!["squat 195 x20"]);
+        assert_eq!(assistance, vec!["front squat 108 x20"]);
+    }
+
+    #[test]
+    fn seventh_week_deload_and_tm_test_sets() {
+        let training_maxes = baseline_training_maxes();
+        let deload = to_strings(
+            &generate_primary_sets(
+                &Lift::Squat,
+                &Week::Week7(SeventhWeek::Deload),
+                &PrimaryVariant::FivesPro,
+                0,
+                &Scheme::default(),
+                &RoundingRules::default(),
+                &training_maxes,
+            )
+            .unwrap(),
+        );
+        assert_eq!(
+            &deload[3..],
+            &[
+                "squat 228 x5",
+                "squat 260 x3-5",
+                "squat 293 x1",
+                "squat 325 x1"
+            ]
+        );
+
+        let tm_test = to_strings(
+            &generate_primary_sets(
+                &Lift::Squat,
+                &Week::Week7(SeventhWeek::TmTest),
+                &PrimaryVariant::Standard,
+                0,
+                &Scheme::default(),
+                &RoundingRules::default(),
+                &training_maxes,
+            )
+            .unwrap(),
+        );
+        assert_eq!(
+            &tm_test[3..],
+            &[
+                "squat 228 x5",
+                "squat 260 x5",
+                "squat 293 x5",
+                "squat 325 x5 (TM test)"
+            ]
+        );
+    }
+
+    #[test]
+    fn tm_test_passes_with_five_reps_and_resets_otherwise() {
+        let training_maxes = baseline_training_maxes();
+        assert_eq!(
+            evaluate_tm_test(&Lift::Squat, 5, &RoundingRules::default(), &training_maxes),
+            Ok(TmTestResult::Pass)
+        );
+        let failed =
+            evaluate_tm_test(&Lift::Squat, 3, &RoundingRules::default(), &training_maxes).unwrap();
+        assert_eq!(failed, TmTestResult::Fail { reset_to: 293.0 });
+        assert_eq!(failed.to_string(), "fail, reset training max to 293");
+    }
 }
 
-/// Simplest strength template (SST) set generator
-pub fn generate_assistance_sets(
-    primary_lift: &Lift,
+/// Supplemental template kinds that can be selected from the CLI or config
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumString)]
+pub enum SupplementalTemplate {
+    /// No supplemental work
+    #[strum(serialize = "none")]
+    None,
+    /// Boring But Big: 5 sets of 10 at a fixed percent of training max
+    #[strum(
+        serialize = "bbb",
+        serialize = "boring_but_big",
+        serialize = "boring-but-big"
+    )]
+    BoringButBig,
+    /// First Set Last: back-off sets at the first working set's weight
+    #[strum(
+        serialize = "fsl",
+        serialize = "first_set_last",
+        serialize = "first-set-last"
+    )]
+    FirstSetLast,
+    /// Second Set Last: back-off sets at the second working set's weight
+    #[strum(
+        serialize = "ssl",
+        serialize = "second_set_last",
+        serialize = "second-set-last"
+    )]
+    SecondSetLast,
+    /// A single high-rep set after the working sets
+    #[strum(serialize = "widowmaker", serialize = "wm")]
+    Widowmaker,
+}
+
+/// Sets x reps scheme for supplemental work, e.g. "5x5"
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SetsReps {
+    /// Number of sets
+    pub sets: i8,
+    /// Reps per set
+    pub reps: i8,
+}
+
+impl FromStr for SetsReps {
+    type Err = String;
+
+    fn from_str(src: &str) -> Result<Self, Self::Err> {
+        let err = || format!("Invalid sets x reps scheme '{src}'. Expected e.g. 5x5 or 3x8.");
+        let (sets, reps) = src.split_once('x').ok_or_else(err)?;
+        let sets: i8 = sets.trim().parse().map_err(|_| err())?;
+        let reps: i8 = reps.trim().parse().map_err(|_| err())?;
+        if sets <= 0 || reps <= 0 {
+            return Err(err());
+        }
+        Ok(SetsReps { sets, reps })
+    }
+}
+
+impl fmt::Display for SetsReps {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}x{}", self.sets, self.reps)
+    }
+}
+
+/// Percentage of training max used for Boring But Big sets
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumString)]
+pub enum BbbPercent {
+    /// 50% every week
+    #[strum(serialize = "50")]
+    Fifty,
+    /// 60% every week
+    #[strum(serialize = "60")]
+    Sixty,
+    /// 70% every week
+    #[strum(serialize = "70")]
+    Seventy,
+    /// 50%, 60%, 70% in weeks 1, 2, 3
+    #[strum(serialize = "ascending")]
+    Ascending,
+    /// 70%, 60%, 50% in weeks 1, 2, 3
+    #[strum(serialize = "descending")]
+    Descending,
+}
+
+impl BbbPercent {
+    /// Training max multiplier for the given week.
+    pub fn scalar(&self, week: &Week) -> f32 {
+        match (self, week) {
+            (BbbPercent::Fifty, _) => 0.5,
+            (BbbPercent::Sixty, _) => 0.6,
+            (BbbPercent::Seventy, _) => 0.7,
+            (BbbPercent::Ascending, Week::Week1) | (BbbPercent::Descending, Week::Week3) => 0.5,
+            (BbbPercent::Ascending, Week::Week2) | (BbbPercent::Descending, Week::Week2) => 0.6,
+            (BbbPercent::Ascending, _) | (BbbPercent::Descending, _) => 0.7,
+        }
+    }
+}
+
+impl fmt::Display for BbbPercent {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s: &str = match self {
+            BbbPercent::Fifty => "50%",
+            BbbPercent::Sixty => "60%",
+            BbbPercent::Seventy => "70%",
+            BbbPercent::Ascending => "50/60/70%",
+            BbbPercent::Descending => "70/60/50%",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+/// Which lift a Widowmaker set is done with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumString)]
+pub enum WidowmakerLift {
+    /// The session's primary lift
+    #[strum(serialize = "primary")]
+    Primary,
+    /// The big assistance lift paired with the primary lift
+    #[strum(
+        serialize = "assistance",
+        serialize = "big_assistance",
+        serialize = "big-assistance"
+    )]
+    BigAssistance,
+}
+
+/// Supplemental work done after the primary lift's working sets
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Supplemental {
+    /// 5 sets of 10 at the given percent of training max
+    BoringButBig(BbbPercent),
+    /// Back-off sets at the first working set's weight
+    FirstSetLast(SetsReps),
+    /// Back-off sets at the second working set's weight
+    SecondSetLast(SetsReps),
+    /// A single high-rep set at `percent` of the chosen lift's training max
+    Widowmaker {
+        /// Lift the set is done with
+        lift: WidowmakerLift,
+        /// Percent of the lift's training max
+        percent: u8,
+        /// Reps to do in the one set
+        reps: i8,
+    },
+}
+
+impl fmt::Display for Supplemental {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Supplemental::BoringButBig(percent) => write!(f, "Boring But Big 5x10 at {}", percent),
+            Supplemental::FirstSetLast(scheme) => write!(f, "First Set Last {}", scheme),
+            Supplemental::SecondSetLast(scheme) => write!(f, "Second Set Last {}", scheme),
+            Supplemental::Widowmaker {
+                lift,
+                percent,
+                reps,
+            } => {
+                write!(f, "Widowmaker 1x{} at {}%", reps, percent)?;
+                if lift == &WidowmakerLift::BigAssistance {
+                    write!(f, " of big assistance")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Supplemental set generator
+pub fn generate_supplemental_sets(
+    lift: &Lift,
     week: &Week,
-    training_maxes: &HashMap<Lift, i16>,
-    rng: &mut impl Rng,
-) -> Result<Vec<String>, WorkoutError> {
+    supplemental: &Supplemental,
+    scheme: &Scheme,
+    rounding: &RoundingRules,
+    training_maxes: &HashMap<Lift, f32>,
+) -> Result<Vec<SetGroup>, WorkoutError> {
     let mut ret = vec![];
+    let training_max = *training_maxes
+        .get(lift)
+        .ok_or(WorkoutError::MissingTrainingMax { lift: *lift })?;
 
-    let make_set_str = |lift: Lift, scalar: f32, sets: i8, reps: i8| -> Result<String, WorkoutError> {
-        let training_max = *training_maxes
-            .get(&lift)
-            .ok_or(WorkoutError::MissingTrainingMax { lift })?;
+    // no supplemental volume for deload weeks
+    if week.is_deload() {
+        return Ok(ret);
+    }
+
+    // back-off sets at the weight of one of this week's working sets
+    let week_scheme = week_scheme(scheme, week)?;
+    let back_off_sets = |index: usize, sets_reps: &SetsReps| -> Result<SetGroup, WorkoutError> {
+        let working_set = week_scheme.working.get(index).ok_or_else(|| {
+            WorkoutError::Config(format!(
+                "Scheme has no working set {} for {}",
+                index + 1,
+                supplemental
+            ))
+        })?;
         Ok(SetGroup {
-            lift,
-            weight: scale(training_max, scalar),
-            sets,
-            reps,
+            lift: *lift,
+            weight: scale(training_max, working_set.scalar, rounding.for_lift(lift)),
+            scalar: Some(working_set.scalar),
+            sets: sets_reps.sets,
+            reps: sets_reps.reps,
+            max_reps: None,
             amrap: false,
-        }
-        .to_string())
+            note: None,
+        })
     };
 
-    // big assistance
-    let big_assistance_lift = match primary_lift {
-        Lift::Squat => Lift::PowerClean,
-        Lift::Deadlift => Lift::FrontSquat,
-        Lift::BenchPress => Lift::InclinePress,
-        Lift::OverheadPress => Lift::CloseGripBenchPress,
-        _ => {
-            return Err(WorkoutError::Config(format!(
-                "Unsupported primary lift {}",
-                primary_lift
-            )))
-        }
-    };
-    match (big_assistance_lift, week) {
-        (Lift::PowerClean, Week::Week4) => {
-            ret.push(make_set_str(big_assistance_lift, 0.5, 1, 3)?);
-            ret.push(make_set_str(big_assistance_lift, 0.6, 1, 3)?);
-            ret.push(make_set_str(big_assistance_lift, 0.7, 1, 3)?);
-        }
-        (Lift::PowerClean, _) => {
-            ret.push(make_set_str(big_assistance_lift, 0.65, 1, 3)?);
-            ret.push(make_set_str(big_assistance_lift, 0.75, 1, 3)?);
-            ret.push(make_set_str(big_assistance_lift, 0.85, 1, 3)?);
-        }
-        (_, Week::Week1) => {
-            ret.push(make_set_str(big_assistance_lift, 0.5, 1, 10)?);
-            ret.push(make_set_str(big_assistance_lift, 0.6, 1, 10)?);
-            ret.push(make_set_str(big_assistance_lift, 0.7, 1, 10)?);
-        }
-        (_, Week::Week2) => {
-            ret.push(make_set_str(big_assistance_lift, 0.6, 1, 8)?);
-            ret.push(make_set_str(big_assistance_lift, 0.7, 1, 8)?);
-            ret.push(make_set_str(big_assistance_lift, 0.8, 1, 6)?);
-        }
-        (_, Week::Week3) => {
-            ret.push(make_set_str(big_assistance_lift, 0.65, 1, 5)?);
-            ret.push(make_set_str(big_assistance_lift, 0.75, 1, 5)?);
-            ret.push(make_set_str(big_assistance_lift, 0.85, 1, 5)?);
-        }
-        (_, Week::Week4) => {
-            ret.push(make_set_str(big_assistance_lift, 0.4, 1, 5)?);
-            ret.push(make_set_str(big_assistance_lift, 0.5, 1, 5)?);
-            ret.push(make_set_str(big_assistance_lift, 0.6, 1, 5)?);
+    match supplemental {
+        Supplemental::BoringButBig(percent) => ret.push(SetGroup {
+            lift: *lift,
+            weight: scale(training_max, percent.scalar(week), rounding.for_lift(lift)),
+            scalar: Some(percent.scalar(week)),
+            sets: 5,
+            reps: 10,
+            max_reps: None,
+            amrap: false,
+            note: None,
+        }),
+        Supplemental::FirstSetLast(sets_reps) => ret.push(back_off_sets(0, sets_reps)?),
+        Supplemental::SecondSetLast(sets_reps) => ret.push(back_off_sets(1, sets_reps)?),
+        Supplemental::Widowmaker {
+            lift: widowmaker_lift,
+            percent,
+            reps,
+        } => {
+            let set_lift = match widowmaker_lift {
+                WidowmakerLift::Primary => *lift,
+                WidowmakerLift::BigAssistance => lift.big_assistance().ok_or_else(|| {
+                    WorkoutError::Config(format!("Unsupported primary lift {}", lift))
+                })?,
+            };
+            let set_training_max = *training_maxes
+                .get(&set_lift)
+                .ok_or(WorkoutError::MissingTrainingMax { lift: set_lift })?;
+            ret.push(SetGroup {
+                lift: set_lift,
+                weight: scale(
+                    set_training_max,
+                    *percent as f32 / 100.0,
+                    rounding.for_lift(&set_lift),
+                ),
+                scalar: Some(*percent as f32 / 100.0),
+                sets: 1,
+                reps: *reps,
+                max_reps: None,
+                amrap: false,
+                note: None,
+            });
         }
     }
 
+    Ok(ret)
+}
+
+/// Simplest strength template (SST) set generator: the big assistance lift's
+/// sets followed by the small assistance exercises
+pub fn generate_assistance_sets(
+    primary_lift: &Lift,
+    week: &Week,
+    scheme: &Scheme,
+    rounding: &RoundingRules,
+    training_maxes: &HashMap<Lift, f32>,
+    rng: &mut impl Rng,
+) -> Result<Vec<Exercise>, WorkoutError> {
+    let mut ret: Vec<Exercise> =
+        generate_big_assistance_sets(primary_lift, week, scheme, rounding, training_maxes)?
+            .into_iter()
+            .map(Exercise::from)
+            .collect();
+    ret.extend(
+        generate_small_assistance(primary_lift, week, rng)?
+            .into_iter()
+            .map(Exercise::Text),
+    );
+    Ok(ret)
+}
+
+/// Sets of the big assistance lift paired with the primary lift
+pub fn generate_big_assistance_sets(
+    primary_lift: &Lift,
+    week: &Week,
+    scheme: &Scheme,
+    rounding: &RoundingRules,
+    training_maxes: &HashMap<Lift, f32>,
+) -> Result<Vec<SetGroup>, WorkoutError> {
+    let big_assistance_lift = primary_lift.big_assistance().ok_or_else(|| {
+        WorkoutError::Config(format!("Unsupported primary lift {}", primary_lift))
+    })?;
+    let training_max =
+        *training_maxes
+            .get(&big_assistance_lift)
+            .ok_or(WorkoutError::MissingTrainingMax {
+                lift: big_assistance_lift,
+            })?;
+
+    let week_scheme = week_scheme(scheme, week)?;
+    let big_assistance_sets = match big_assistance_lift {
+        Lift::PowerClean => &week_scheme.explosive_assistance,
+        _ => &week_scheme.assistance,
+    };
+    Ok(big_assistance_sets
+        .iter()
+        .map(|set| SetGroup {
+            lift: big_assistance_lift,
+            weight: scale(
+                training_max,
+                set.scalar,
+                rounding.for_lift(&big_assistance_lift),
+            ),
+            scalar: Some(set.scalar),
+            sets: 1,
+            reps: set.reps,
+            max_reps: set.max_reps,
+            amrap: false,
+            note: None,
+        })
+        .collect())
+}
+
+/// Small assistance exercises for the primary lift's session
+pub fn generate_small_assistance(
+    primary_lift: &Lift,
+    week: &Week,
+    rng: &mut impl Rng,
+) -> Result<Vec<String>, WorkoutError> {
+    let mut ret = vec![];
     // small assistance
     match primary_lift {
         Lift::Squat => {
@@ -399,7 +1540,7 @@ pub fn generate_assistance_sets(
             });
         }
         Lift::Deadlift => {
-            let sets = if week == &Week::Week4 { 2 } else { 3 };
+            let sets = if week.is_deload() { 2 } else { 3 };
             ret.push(format!("overhead squat, {}x10", sets));
         }
         Lift::BenchPress => {
@@ -412,14 +1553,14 @@ pub fn generate_assistance_sets(
         }
         Lift::OverheadPress => {
             let coin: bool = rng.gen();
-            let is_week4 = week == &Week::Week4;
+            let is_deload = week.is_deload();
             ret.push(if coin {
-                if is_week4 {
+                if is_deload {
                     "barbell 21s x2".to_owned()
                 } else {
                     "barbell 21s x3".to_owned()
                 }
-            } else if is_week4 {
+            } else if is_deload {
                 "Kroc row, 2x20".to_owned()
             } else {
                 "Kroc row, 3x20".to_owned()
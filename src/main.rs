@@ -1,29 +1,40 @@
 #![cfg_attr(debug_assertions, allow(dead_code, unused_imports))]
-extern crate strum;
-extern crate strum_macros;
 
-use clap::Parser;
-use rand::seq::SliceRandom;
+use chrono::NaiveDate;
+use clap::{Args, Parser, Subcommand};
 use rand::rngs::StdRng;
 use rand::SeedableRng;
-use serde::Deserialize;
-use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::process;
 use std::str::FromStr; // required by EnumString
 
-mod lifts;
-mod static_strings;
-
-use lifts::{generate_assistance_sets, generate_primary_sets, Lift, Week, WorkoutError};
-use static_strings::{CORE_EXERCISES, LIMBER_11, WARM_UP};
+use five_three_one::block::BlockPhase;
+use five_three_one::config::{
+    parse_bbb_percent, parse_block_phase, parse_display_unit, parse_e1rm_formula,
+    parse_primary_lift, parse_primary_variant, parse_schedule, parse_sets_reps, parse_seventh_week,
+    parse_supplemental_template, parse_week, progress_training_maxes, read_config_file, Config,
+    SupplementalChoice,
+};
+use five_three_one::csv;
+use five_three_one::e1rm::E1rmFormula;
+use five_three_one::format::Format;
+use five_three_one::html;
+use five_three_one::json;
+use five_three_one::lifts::{
+    generate_primary_sets, BbbPercent, Lift, PrimaryVariant, SetsReps, SeventhWeek,
+    SupplementalTemplate, Week, WorkoutError, MAX_JOKERS,
+};
+use five_three_one::markdown;
+use five_three_one::plates::PlateInventory;
+use five_three_one::schedule::Schedule;
+use five_three_one::session::{Session, SessionOptions, WorkoutOptions};
+use five_three_one::text;
+use five_three_one::training_log::{append_to_log, read_log, LoggedSession};
+use five_three_one::units::{DisplayUnit, Units};
+use five_three_one::workout::Workout;
 
 const DEFAULT_TRAINING_MAX_FILE: &str = "training_max.toml";
-
-#[derive(Deserialize)]
-struct TrainingMaxConfig {
-    default: HashMap<String, i32>,
-}
+const DEFAULT_TRAINING_LOG_FILE: &str = "training_log.toml";
 
 /*
  * ============================================================
@@ -31,50 +42,18 @@ struct TrainingMaxConfig {
  * ============================================================
  */
 
-fn parse_primary_lift(src: &str) -> Result<Lift, String> {
-    let lift = Lift::from_str(src).map_err(|_| {
+fn parse_format(src: &str) -> Result<Format, String> {
+    Format::from_str(src).map_err(|_| {
         format!(
-            "Invalid primary lift '{src}'. Valid values are: squat/s/bench-press/bench_press/b/bp/deadlift/d/dl/overhead-press/o/ohp/p."
+            "Invalid format '{}'. Valid values are: text/json/markdown/csv/html.",
+            src
         )
-    })?;
-
-    if !Lift::PRIMARY_LIFTS.contains(&lift) {
-        return Err(format!(
-            "Invalid primary lift '{src}'. Valid values are: squat/s/bench-press/bench_press/b/bp/deadlift/d/dl/overhead-press/o/ohp/p."
-        ));
-    }
-
-    Ok(lift)
-}
-
-fn parse_week(src: &str) -> Result<Week, String> {
-    match src {
-        "1" => Ok(Week::Week1),
-        "2" => Ok(Week::Week2),
-        "3" => Ok(Week::Week3),
-        "4" => Ok(Week::Week4),
-        _ => Err("week must be 1, 2, 3, or 4".to_owned()),
-    }
+    })
 }
 
-fn validate_required_assistance_training_max(
-    primary_lift: &Lift,
-    training_maxes: &HashMap<Lift, i16>,
-) -> Result<(), WorkoutError> {
-    let required_lift = match primary_lift {
-        Lift::Squat => Lift::PowerClean,
-        Lift::Deadlift => Lift::FrontSquat,
-        Lift::BenchPress => Lift::InclinePress,
-        Lift::OverheadPress => Lift::CloseGripBenchPress,
-        _ => return Ok(()),
-    };
-
-    training_maxes
-        .get(&required_lift)
-        .map(|_| ())
-        .ok_or(WorkoutError::MissingTrainingMax { lift: required_lift })?;
-
-    Ok(())
+fn parse_date(src: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(src, "%Y-%m-%d")
+        .map_err(|_| format!("Invalid date '{src}'. Expected YYYY-MM-DD."))
 }
 
 #[derive(Parser, Debug)]
@@ -88,99 +67,242 @@ The program reads lift training maxes from a TOML file that follows:\n\
 [default]\nsquat = 325\nbench_press = 235\ndeadlift = 365\noverhead_press = 170\n\
 \n\
 By default, it looks for `training_max.toml` in the current working directory.",
+    subcommand_negates_reqs = true
 )]
 struct Cli {
-    /// Primary lift for the week that will be done in the 5/3/1 rep pattern.
-    /// Examples: `squat`, `s`, `bench-press`, `bench_press`, `b`, `bp`,
-    /// `deadlift`, `d`, `dl`, `overhead-press`, `ohp`, `o`, or `p`.
-    #[arg(short = 'l', long, value_parser = parse_primary_lift)]
-    primary_lift: Lift,
+    #[command(subcommand)]
+    command: Option<Command>,
 
-    /// Week number (1-4) in the 5/3/1 cycle for the primary lift.
-    #[arg(short = 'n', long, value_parser = parse_week)]
-    week: Week,
+    #[command(flatten)]
+    session: SessionArgs,
+
+    /// Reps completed on the 100% set of a TM test week; reports whether to
+    /// keep or reset the training max.
+    #[arg(long, value_name = "REPS")]
+    tm_test_reps: Option<i8>,
 
     /// Include warm-up?
-    #[arg(short = 'w', long)]
+    #[arg(short = 'w', long, global = true)]
     warmup: bool,
 
     /// Include mobility?
-    #[arg(short = 'm', long)]
+    #[arg(short = 'm', long, global = true)]
     mobility: bool,
 
     /// Number of core exercises to include (randomly selected from the built-in list).
-    #[arg(default_value = "0", short = 'x', long, value_name = "N")]
+    #[arg(
+        default_value = "0",
+        short = 'x',
+        long,
+        value_name = "N",
+        global = true
+    )]
     core_exercises: usize,
 
+    /// Print the plates to load on each side of the bar for every barbell set.
+    #[arg(long, global = true)]
+    plates: bool,
+
+    /// Supplemental template done after the primary lift: `none`, `bbb`,
+    /// `fsl` (First Set Last), `ssl` (Second Set Last), or `widowmaker`.
+    /// Overrides `template` in the config file's `[supplemental]` table.
+    #[arg(long, value_name = "TEMPLATE", value_parser = parse_supplemental_template, global = true)]
+    supplemental: Option<SupplementalTemplate>,
+
+    /// Percentage of training max for Boring But Big sets: `50`, `60`, `70`,
+    /// `ascending` (50/60/70 in weeks 1-3), or `descending` (70/60/50).
+    #[arg(long, value_name = "PERCENT", value_parser = parse_bbb_percent, global = true)]
+    bbb_percent: Option<BbbPercent>,
+
+    /// Sets x reps for FSL/SSL back-off sets, e.g. `5x5` (default) or `3x8`.
+    #[arg(long, value_name = "SETSxREPS", value_parser = parse_sets_reps, global = true)]
+    supplemental_scheme: Option<SetsReps>,
+
     /// Path to a TOML config file. Defaults to `training_max.toml` in cwd.
-    #[arg(long = "config", value_name = "PATH")]
+    #[arg(long = "config", value_name = "PATH", global = true)]
     config_path: Option<PathBuf>,
 
+    /// Path to the training log written by `log`. Defaults to
+    /// `training_log.toml` in cwd.
+    #[arg(long = "log", value_name = "PATH", global = true)]
+    log_path: Option<PathBuf>,
+
+    /// Formula for estimated one-rep maxes from AMRAP sets: `wendler` (the
+    /// default), `epley`, `brzycki`, or `lombardi`. Overrides `formula` in the
+    /// config file's `[e1rm]` table.
+    #[arg(long, value_name = "FORMULA", value_parser = parse_e1rm_formula, global = true)]
+    e1rm_formula: Option<E1rmFormula>,
+
+    /// Unit(s) to print weights in: `lb`, `kg`, or `both`. Weights in another
+    /// unit than the config file's are converted. Overrides `display` in the
+    /// config file's `[units]` table.
+    #[arg(long = "units", value_name = "UNITS", value_parser = parse_display_unit, global = true)]
+    display_unit: Option<DisplayUnit>,
+
+    /// Training days per week for `cycle` and `schedule`: `4` (one primary
+    /// lift per day, the default), `3` (one lift per day, rotating through the
+    /// lifts), or `2` (two lifts per day). Overrides `days` in the config
+    /// file's `[schedule]` table.
+    #[arg(long = "days", value_name = "N", value_parser = parse_schedule, global = true)]
+    schedule: Option<Schedule>,
+
     /// Seed for RNG to make assistance/core selection deterministic.
-    #[arg(long)]
+    #[arg(long, global = true)]
     seed: Option<u64>,
+
+    /// Output format for sessions and `cycle`: `text` (the default), `json`
+    /// (see the README for the schema), `markdown`, `csv` or `html` (a printable
+    /// sheet, always with plates).
+    #[arg(default_value = "text", long, value_name = "FORMAT", value_parser = parse_format, global = true)]
+    format: Format,
+
+    /// Date of the session as YYYY-MM-DD, for `log` and CSV output. Defaults to
+    /// today.
+    #[arg(long, value_name = "DATE", value_parser = parse_date, global = true)]
+    date: Option<NaiveDate>,
 }
 
-/*
- * ============================================================
- * Config data loading
- * ============================================================
- */
+/// Options that pick the primary lift work for a session
+#[derive(Args, Debug)]
+struct SessionArgs {
+    /// Primary lift for the week that will be done in the 5/3/1 rep pattern.
+    /// Examples: `squat`, `s`, `bench-press`, `bench_press`, `b`, `bp`,
+    /// `deadlift`, `d`, `dl`, `overhead-press`, `ohp`, `o`, or `p`.
+    #[arg(short = 'l', long, required = true, value_parser = parse_primary_lift)]
+    primary_lift: Option<Lift>,
+
+    /// Week number (1-4) in the 5/3/1 cycle for the primary lift, or 7 for
+    /// the 7th week. With `--block`, the week (1-3 or 7) of the cycle.
+    #[arg(short = 'n', long, required = true, value_parser = parse_week)]
+    week: Option<Week>,
+
+    /// 7th week protocol for `--week 7`: `deload` (70/80/90/100% for 5,
+    /// 3-5, 1, 1) or `tm-test` (70/80/90/100% for 5). Defaults to `deload`, or
+    /// to the phase's `seventh_week` with `--block`.
+    #[arg(long, value_name = "PROTOCOL", value_parser = parse_seventh_week)]
+    seventh_week: Option<SeventhWeek>,
+
+    /// Phase of a Leader/Anchor block (`leader` or `anchor`). The phase's
+    /// programming from the config file's `[block]` table picks the variant
+    /// and supplemental template unless they are given explicitly.
+    #[arg(long, value_name = "PHASE", value_parser = parse_block_phase)]
+    block: Option<BlockPhase>,
+
+    /// Cycle number within the `--block` phase.
+    #[arg(default_value = "1", long, value_name = "N", requires = "block")]
+    cycle: u8,
+
+    #[command(flatten)]
+    program: ProgramArgs,
+}
 
-fn load_training_maxes_from_file(path: &Path) -> Result<HashMap<Lift, i16>, WorkoutError> {
-    let source = path.to_string_lossy().into_owned();
-    let contents = std::fs::read_to_string(path)
-        .map_err(|err| WorkoutError::Config(format!("Unable to read {}: {}", source, err)))?;
-    parse_training_maxes_from_str(&contents, &source)
+/// Options for how the primary lift is programmed, shared by single sessions
+/// and whole cycles
+#[derive(Args, Debug)]
+struct ProgramArgs {
+    /// How the primary lift's working sets are done: `standard` (5/3/1 reps
+    /// with an AMRAP top set, the default), `5s-pro` (straight sets of 5, no
+    /// AMRAP), or `pyramid` (back down through the working weights, last down
+    /// set AMRAP).
+    #[arg(long, value_name = "VARIANT", value_parser = parse_primary_variant)]
+    variant: Option<PrimaryVariant>,
+
+    /// Number of optional Joker sets (up to 3) after the AMRAP top set: triples in
+    /// week 2, singles in week 3, each `--joker-increment` heavier than the last.
+    #[arg(default_value = "0", long, value_name = "N", value_parser = clap::value_parser!(u8).range(0..=MAX_JOKERS as i64))]
+    jokers: u8,
+
+    /// Percent of training max each Joker set adds to the one before it, from 5
+    /// to 10.
+    #[arg(default_value = "5", long, value_name = "PERCENT", value_parser = clap::value_parser!(u8).range(5..=10))]
+    joker_increment: u8,
+
+    /// Percentage scheme: built-in `531` (default) or `351`, or a scheme
+    /// defined under `[schemes]` in the config file. Overrides `name` in the
+    /// config file's `[scheme]` table.
+    #[arg(long, value_name = "NAME")]
+    scheme: Option<String>,
 }
 
-fn parse_training_maxes_from_str(
-    contents: &str,
-    source: &str,
-) -> Result<HashMap<Lift, i16>, WorkoutError> {
-    let cfg: TrainingMaxConfig = toml::from_str(contents)
-        .map_err(|err| WorkoutError::Config(format!("Unable to parse {} as TOML: {}", source, err)))?;
-
-    let mut ret = HashMap::new();
-    for (lift_name, raw_weight) in cfg.default.iter() {
-        let lift = Lift::from_str(lift_name).map_err(|_| {
-            WorkoutError::Config(format!(
-                "Unknown lift '{}' in training max file {}",
-                lift_name, source
-            ))
-        })?;
-
-        if *raw_weight <= 0 {
-            return Err(WorkoutError::Config(format!(
-                "Training max for '{}' in {} must be a positive integer, got {}",
-                lift_name, source, raw_weight
-            )));
-        }
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Bump every training max for the next cycle and write the config file back.
+    Progress(ProgressArgs),
+    /// Record a completed session, with the reps done on its AMRAP sets, in the training log.
+    Log(LogArgs),
+    /// Print all four weeks of a cycle for every primary lift, grouped by week and day.
+    Cycle(CycleArgs),
+    /// Print which lifts and week each session of the training schedule is for.
+    Schedule(ScheduleArgs),
+}
 
-        let weight =
-            i16::try_from(*raw_weight).map_err(|_| WorkoutError::Config(format!(
-                "Training max for '{}' in {} is out of range: {}",
-                lift_name, source, raw_weight
-            )))?;
+#[derive(Args, Debug)]
+struct ProgressArgs {
+    /// Increment for upper body lifts. Overrides `upper_body` in the config
+    /// file's `[progression]` table; defaults to 5 lb or 2.5 kg.
+    #[arg(long, value_name = "N")]
+    upper_body: Option<f32>,
 
-        ret.insert(lift, weight);
-    }
+    /// Increment for lower body lifts. Overrides `lower_body` in the config
+    /// file's `[progression]` table; defaults to 10 lb or 5 kg.
+    #[arg(long, value_name = "N")]
+    lower_body: Option<f32>,
+
+    /// Print the new training maxes without writing the config file.
+    #[arg(long)]
+    dry_run: bool,
+}
+
+#[derive(Args, Debug)]
+struct LogArgs {
+    #[command(flatten)]
+    session: SessionArgs,
+
+    /// Reps completed on an AMRAP set; give once per AMRAP set, in order.
+    #[arg(long = "amrap-reps", value_name = "REPS")]
+    amrap_reps: Vec<i8>,
+
+    /// Free-form notes about the session.
+    #[arg(long)]
+    notes: Option<String>,
+}
 
-    let missing_primary_lifts: Vec<String> = Lift::PRIMARY_LIFTS
-        .iter()
-        .filter(|lift| !ret.contains_key(lift))
-        .map(|lift| lift.to_string())
-        .collect();
-
-    if !missing_primary_lifts.is_empty() {
-        return Err(WorkoutError::Config(format!(
-            "Missing required primary lift training max(es) in {}: {}",
-            source,
-            missing_primary_lifts.join(", ")
-        )));
+#[derive(Args, Debug)]
+struct CycleArgs {
+    #[command(flatten)]
+    program: ProgramArgs,
+}
+
+#[derive(Args, Debug)]
+struct ScheduleArgs {
+    /// Only print session N, counting from 1 since starting the schedule.
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u32).range(1..))]
+    session: Option<u32>,
+}
+
+impl ProgramArgs {
+    /// Session options for these program options, outside of a block
+    fn session_options(&self, supplemental: SupplementalChoice) -> SessionOptions {
+        SessionOptions {
+            variant: self.variant,
+            jokers: self.jokers,
+            joker_increment: Some(self.joker_increment as f32 / 100.0),
+            scheme: self.scheme.clone(),
+            supplemental,
+            ..Default::default()
+        }
     }
+}
 
-    Ok(ret)
+impl Cli {
+    fn supplemental_choice(&self) -> SupplementalChoice {
+        SupplementalChoice {
+            template: self.supplemental,
+            bbb_percent: self.bbb_percent,
+            scheme: self.supplemental_scheme,
+            ..Default::default()
+        }
+    }
 }
 
 /*
@@ -190,11 +312,16 @@ fn parse_training_maxes_from_str(
  */
 
 fn print_header(text: &str) {
-    println!("{}\n====================", text);
+    print!("{}", text::header(text));
+}
+
+/// Title for one session of a multi-session plan
+fn print_title(text: &str) {
+    println!("####################\n{}\n####################", text);
 }
 
 fn print_spacer() {
-    println!("\n");
+    print!("{}", text::SPACER);
 }
 
 /*
@@ -217,198 +344,648 @@ fn run() -> Result<(), WorkoutError> {
         .as_deref()
         .unwrap_or_else(|| Path::new(DEFAULT_TRAINING_MAX_FILE));
 
-    let training_maxes = load_training_maxes_from_file(config_path)?;
-    validate_required_assistance_training_max(&args.primary_lift, &training_maxes)?;
+    let source = config_path.to_string_lossy().into_owned();
+    let contents = read_config_file(config_path)?;
+    let config = Config::parse(&contents, &source)?;
 
-    let mut rng = match args.seed {
-        Some(seed) => StdRng::seed_from_u64(seed),
-        None => StdRng::from_entropy(),
+    let log_path = args
+        .log_path
+        .as_deref()
+        .unwrap_or_else(|| Path::new(DEFAULT_TRAINING_LOG_FILE));
+    let e1rm_formula = match args.e1rm_formula {
+        Some(formula) => formula,
+        None => config.e1rm_formula()?.unwrap_or(E1rmFormula::Wendler),
     };
+    let mut units = config.units()?;
+    if args.display_unit.is_some() {
+        units.display = args.display_unit;
+    }
 
-    if args.warmup {
-        print_header("Warm-up");
-        for &s in WARM_UP.iter() {
-            println!("  {}", s);
-        }
-        print_spacer();
+    if args.format != Format::Text
+        && matches!(
+            args.command,
+            Some(Command::Progress(_) | Command::Log(_) | Command::Schedule(_))
+        )
+    {
+        return Err(WorkoutError::Config(
+            "--format is only supported for sessions and cycle".to_owned(),
+        ));
     }
 
-    if args.mobility {
-        print_header("Limber 11");
-        for &s in LIMBER_11.iter() {
-            println!("  {}", s);
+    match &args.command {
+        Some(Command::Progress(progress_args)) => {
+            run_progress(progress_args, config_path, &contents, &config)
         }
-        print_spacer();
+        Some(Command::Log(log_args)) => run_log(
+            log_args,
+            args.supplemental_choice(),
+            resolve_date(args.date),
+            log_path,
+            &e1rm_formula,
+            &units,
+            &config,
+        ),
+        Some(Command::Cycle(cycle_args)) => {
+            run_cycle(&args, cycle_args, log_path, &e1rm_formula, &units, &config)
+        }
+        Some(Command::Schedule(schedule_args)) => run_schedule(&args, schedule_args, &config),
+        None => run_session(&args, log_path, &e1rm_formula, &units, &config),
     }
+}
 
-    print_header("Primary lift");
-    let primary_sets = generate_primary_sets(&args.primary_lift, &args.week, &training_maxes)?;
-    for s in primary_sets.iter() {
-        println!("  {}", s);
+fn run_progress(
+    args: &ProgressArgs,
+    config_path: &Path,
+    contents: &str,
+    config: &Config,
+) -> Result<(), WorkoutError> {
+    let source = config_path.to_string_lossy().into_owned();
+    let progression = config.progression(args.upper_body, args.lower_body)?;
+    let (new_contents, changes) = progress_training_maxes(contents, &source, &progression)?;
+
+    print_header("Training max progression");
+    for change in changes.iter() {
+        println!(
+            "  {:<24}{:>5} -> {} ({:+})",
+            change.lift.to_string(),
+            change.before,
+            change.after,
+            change.after - change.before
+        );
     }
     print_spacer();
 
-    print_header("Assistance lifts");
-    let assistance_sets = generate_assistance_sets(
-        &args.primary_lift,
-        &args.week,
-        &training_maxes,
-        &mut rng,
+    if args.dry_run {
+        println!("Dry run; {} was not changed.", source);
+    } else {
+        std::fs::write(config_path, new_contents)
+            .map_err(|err| WorkoutError::Config(format!("Unable to write {}: {}", source, err)))?;
+        println!("Wrote new training maxes to {}.", source);
+    }
+
+    Ok(())
+}
+
+fn run_log(
+    args: &LogArgs,
+    supplemental: SupplementalChoice,
+    date: NaiveDate,
+    log_path: &Path,
+    e1rm_formula: &E1rmFormula,
+    units: &Units,
+    config: &Config,
+) -> Result<(), WorkoutError> {
+    let session = resolve_session(&args.session, supplemental, config)?;
+    let set_groups = generate_primary_sets(
+        &session.primary_lift,
+        &session.week,
+        &session.variant,
+        session.jokers,
+        &session.scheme,
+        &session.rounding,
+        &session.training_maxes,
     )?;
-    for s in assistance_sets.iter() {
-        println!("  {}", s);
+    let logged = LoggedSession::new(
+        date,
+        session.primary_lift,
+        session.week,
+        units.unit,
+        &set_groups,
+        &args.amrap_reps,
+        args.notes.clone(),
+    )?;
+    let best_e1rm =
+        e1rm_formula.best_logged(&session.primary_lift, &read_log(log_path)?, units.unit);
+    append_to_log(log_path, &logged)?;
+
+    print_header(&format!(
+        "Logged {} week {} on {}",
+        session.primary_lift,
+        session.week.number(),
+        date
+    ));
+    for (set_group, logged_set) in set_groups.iter().zip(logged.sets.iter()) {
+        let set = text::format_set_group(set_group, units);
+        let reps = match logged_set.actual_reps {
+            Some(reps) => reps,
+            None => {
+                println!("  {}", set);
+                continue;
+            }
+        };
+        match e1rm_formula.estimate(logged_set.weight, reps) {
+            Some(e1rm) if best_e1rm.is_none_or(|best| e1rm > best) => {
+                println!(
+                    "  {}: {} reps, e1RM {} (new best)",
+                    set,
+                    reps,
+                    units.format(e1rm.round())
+                )
+            }
+            Some(e1rm) => println!(
+                "  {}: {} reps, e1RM {}",
+                set,
+                reps,
+                units.format(e1rm.round())
+            ),
+            None => println!("  {}: {} reps", set, reps),
+        }
     }
     print_spacer();
+    println!("Wrote session to {}.", log_path.to_string_lossy());
+
+    Ok(())
+}
+
+/// Resolves a session's programming from the command line options and the
+/// config file.
+fn resolve_session(
+    args: &SessionArgs,
+    supplemental: SupplementalChoice,
+    config: &Config,
+) -> Result<Session, WorkoutError> {
+    let options = SessionOptions {
+        seventh_week: args.seventh_week,
+        block: args.block,
+        cycle: args.cycle,
+        ..args.program.session_options(supplemental)
+    };
+    // both are required by clap unless a subcommand is given
+    config.session(args.primary_lift.unwrap(), args.week.unwrap(), &options)
+}
+
+/// Settings shared by every session printed in one run
+struct SessionOutput<'a> {
+    e1rm_formula: &'a E1rmFormula,
+    units: &'a Units,
+    plates: Option<PlateInventory>,
+    logged: Vec<LoggedSession>,
+}
+
+impl<'a> SessionOutput<'a> {
+    fn new(
+        args: &Cli,
+        log_path: &Path,
+        e1rm_formula: &'a E1rmFormula,
+        units: &'a Units,
+        config: &Config,
+    ) -> Result<SessionOutput<'a>, WorkoutError> {
+        let plates = match args.plates || args.format == Format::Html {
+            true => Some(config.plates(units.unit)?),
+            false => None,
+        };
+        Ok(SessionOutput {
+            e1rm_formula,
+            units,
+            plates,
+            logged: read_log(log_path)?,
+        })
+    }
+}
+
+/// The `--date`, or today
+fn resolve_date(date: Option<NaiveDate>) -> NaiveDate {
+    date.unwrap_or_else(|| chrono::Local::now().date_naive())
+}
+
+/// The `--seed`, or a random one so JSON output can still report the seed used
+fn resolve_seed(seed: Option<u64>) -> u64 {
+    seed.unwrap_or_else(rand::random)
+}
+
+fn run_session(
+    args: &Cli,
+    log_path: &Path,
+    e1rm_formula: &E1rmFormula,
+    units: &Units,
+    config: &Config,
+) -> Result<(), WorkoutError> {
+    let session = resolve_session(&args.session, args.supplemental_choice(), config)?;
+
+    if args.tm_test_reps.is_some() && session.week != Week::Week7(SeventhWeek::TmTest) {
+        return Err(WorkoutError::Config(
+            "--tm-test-reps is only valid for a TM test 7th week".to_owned(),
+        ));
+    }
 
-    if args.core_exercises > 0 {
-        print_header("Core");
-        let core_exercises = CORE_EXERCISES.choose_multiple(&mut rng, args.core_exercises);
-        for &s in core_exercises {
-            println!("  {}", s);
+    let output = SessionOutput::new(args, log_path, e1rm_formula, units, config)?;
+    let seed = resolve_seed(args.seed);
+    let mut rng = StdRng::seed_from_u64(seed);
+    let workout = build_workout(args, &session, &output, &mut rng)?;
+    match args.format {
+        Format::Text => print!(
+            "{}",
+            text::render_workout(&workout, units, output.plates.as_ref())
+        ),
+        Format::Json => {
+            let mut document = json::Document::new(seed, units.unit);
+            document.push(&workout, None, output.plates.as_ref());
+            println!("{}", document.to_json());
+        }
+        Format::Markdown => print!(
+            "{}",
+            markdown::render_workout(&workout, 1, units, output.plates.as_ref())
+        ),
+        Format::Csv => {
+            let mut table = csv::Table::new(units.unit);
+            table.push(&workout, None, Some(resolve_date(args.date)));
+            print!("{}", table.to_csv());
+        }
+        Format::Html => {
+            let mut sheet = html::Sheet::new(
+                &format!("5/3/1 {}", workout.title()),
+                units,
+                output.plates.as_ref(),
+            );
+            sheet.push(&workout, None);
+            print!("{}", sheet.to_html());
         }
-        print_spacer();
     }
 
     Ok(())
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use clap::CommandFactory;
-    use std::collections::HashMap;
-    use std::fs;
-    use std::time::{SystemTime, UNIX_EPOCH};
-
-    fn write_temp_config(contents: &str) -> PathBuf {
-        let mut path = std::env::temp_dir();
-        let unique = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .expect("clock moved backwards")
-            .as_nanos();
-        path.push(format!("five-three-one-config-{}.toml", unique));
-        fs::write(&path, contents).unwrap();
-        path
+/// The `--days` schedule, or the config file's
+fn resolve_schedule(args: &Cli, config: &Config) -> Result<Schedule, WorkoutError> {
+    match args.schedule {
+        Some(schedule) => Ok(schedule),
+        None => Ok(config.schedule()?.unwrap_or_default()),
     }
+}
 
-    #[test]
-    fn parse_week_supports_only_supported_values() {
-        assert_eq!(parse_week("1").unwrap(), Week::Week1);
-        assert_eq!(parse_week("2").unwrap(), Week::Week2);
-        assert_eq!(parse_week("3").unwrap(), Week::Week3);
-        assert_eq!(parse_week("4").unwrap(), Week::Week4);
-        assert_eq!(parse_week("0"), Err("week must be 1, 2, 3, or 4".to_owned()));
-        assert_eq!(parse_week("5"), Err("week must be 1, 2, 3, or 4".to_owned()));
+fn run_schedule(
+    args: &Cli,
+    schedule_args: &ScheduleArgs,
+    config: &Config,
+) -> Result<(), WorkoutError> {
+    let schedule = resolve_schedule(args, config)?;
+
+    print_header(&format!("{} schedule", schedule));
+    match schedule_args.session {
+        Some(number) => {
+            let session = schedule
+                .session(number)
+                .ok_or_else(|| WorkoutError::Config("Sessions are numbered from 1".to_owned()))?;
+            println!("  {}", session);
+            for lift in session.lifts.iter() {
+                println!(
+                    "    five-three-one --primary-lift {} --week {}",
+                    lift.config_key(),
+                    session.week.number()
+                );
+            }
+        }
+        None => {
+            for session in
+                (1..=schedule.sessions_per_cycle()).filter_map(|number| schedule.session(number))
+            {
+                println!("  {}", session);
+            }
+        }
     }
+    print_spacer();
 
-    #[test]
-    fn parse_primary_lift_accepts_supported_aliases() {
-        assert_eq!(parse_primary_lift("squat").unwrap(), Lift::Squat);
-        assert_eq!(parse_primary_lift("s").unwrap(), Lift::Squat);
-        assert_eq!(parse_primary_lift("bench-press").unwrap(), Lift::BenchPress);
-        assert_eq!(parse_primary_lift("bench_press").unwrap(), Lift::BenchPress);
-        assert_eq!(parse_primary_lift("b").unwrap(), Lift::BenchPress);
-        assert_eq!(parse_primary_lift("deadlift").unwrap(), Lift::Deadlift);
-        assert_eq!(parse_primary_lift("dl").unwrap(), Lift::Deadlift);
-        assert_eq!(parse_primary_lift("overhead-press").unwrap(), Lift::OverheadPress);
-        assert_eq!(parse_primary_lift("p").unwrap(), Lift::OverheadPress);
+    Ok(())
+}
+
+/// Prints weeks 1-4 for every primary lift, session by session in the order of
+/// the training schedule. A single RNG is shared by all sessions, so `--seed`
+/// reproduces the whole plan.
+fn run_cycle(
+    args: &Cli,
+    cycle_args: &CycleArgs,
+    log_path: &Path,
+    e1rm_formula: &E1rmFormula,
+    units: &Units,
+    config: &Config,
+) -> Result<(), WorkoutError> {
+    if args.tm_test_reps.is_some() {
+        return Err(WorkoutError::Config(
+            "--tm-test-reps is only valid for a TM test 7th week".to_owned(),
+        ));
     }
 
+    let schedule = resolve_schedule(args, config)?;
+    let output = SessionOutput::new(args, log_path, e1rm_formula, units, config)?;
+    let seed = resolve_seed(args.seed);
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut document = json::Document::new(seed, units.unit);
+    let mut table = csv::Table::new(units.unit);
+    let mut sheet = html::Sheet::new("5/3/1 cycle", units, output.plates.as_ref());
+    for scheduled in
+        (1..=schedule.sessions_per_cycle()).filter_map(|number| schedule.session(number))
+    {
+        match args.format {
+            Format::Text => {
+                print_title(&scheduled.to_string());
+                print_spacer();
+            }
+            Format::Json | Format::Csv | Format::Html => {}
+            Format::Markdown => print!("{}", markdown::heading(1, &scheduled.to_string())),
+        }
+        for lift in scheduled.lifts.iter() {
+            let options = cycle_args
+                .program
+                .session_options(args.supplemental_choice());
+            let mut session = config.session(*lift, scheduled.week, &options)?;
+            // Jokers are only done in the weeks with a triples or singles top set
+            if session.scheme.joker_reps(&session.week).is_none() {
+                session.jokers = 0;
+            }
+            let workout = build_workout(args, &session, &output, &mut rng)?;
+            match args.format {
+                Format::Text => print!(
+                    "{}",
+                    text::render_workout(&workout, units, output.plates.as_ref())
+                ),
+                Format::Json => document.push(&workout, Some(&scheduled), output.plates.as_ref()),
+                Format::Markdown => {
+                    print!(
+                        "{}",
+                        markdown::render_workout(&workout, 2, units, output.plates.as_ref())
+                    )
+                }
+                Format::Csv => table.push(&workout, Some(&scheduled), None),
+                Format::Html => sheet.push(&workout, Some(&scheduled)),
+            }
+        }
+    }
+    match args.format {
+        Format::Json => println!("{}", document.to_json()),
+        Format::Csv => print!("{}", table.to_csv()),
+        Format::Html => print!("{}", sheet.to_html()),
+        Format::Text | Format::Markdown => {}
+    }
+
+    Ok(())
+}
+
+/// Generates a session's workout with the optional parts asked for on the
+/// command line.
+fn build_workout(
+    args: &Cli,
+    session: &Session,
+    output: &SessionOutput,
+    rng: &mut StdRng,
+) -> Result<Workout, WorkoutError> {
+    let options = WorkoutOptions {
+        warm_up: args.warmup,
+        mobility: args.mobility,
+        core_exercises: args.core_exercises,
+        tm_test_reps: args.tm_test_reps,
+        best_e1rm: output
+            .e1rm_formula
+            .best_logged(&session.primary_lift, &output.logged, output.units.unit)
+            .map(|best| (*output.e1rm_formula, best)),
+    };
+    session.workout(&options, output.units, rng)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+    use five_three_one::lifts::Supplemental;
+
     #[test]
-    fn parse_primary_lift_rejects_assistance_lifts() {
-        assert!(parse_primary_lift("front_squat").is_err());
-        assert!(parse_primary_lift("power_clean").is_err());
-        assert!(parse_primary_lift("close_grip_bench_press").is_err());
+    fn cli_parses_defaults_and_config_option_is_optional() {
+        let args = Cli::parse_from(["five-three-one", "--primary-lift", "squat", "--week", "1"]);
+        assert_eq!(args.session.primary_lift, Some(Lift::Squat));
+        assert_eq!(args.session.week, Some(Week::Week1));
+        assert!(args.session.program.variant.is_none());
+        assert!(args.session.block.is_none());
+        assert!(args.config_path.is_none());
     }
 
     #[test]
-    fn parse_training_maxes_from_toml_requires_primary_lifts() {
-        let config = "[default]
-squat = 325
-bench_press = 235
-deadlift = 365";
-        let err = parse_training_maxes_from_str(config, "test-training_max.toml").unwrap_err();
-        assert!(err.to_string().contains("Missing required primary lift training max"));
-        assert!(err.to_string().contains("overhead press"));
+    fn help_output_documents_training_max_toml_path() {
+        let mut cmd = Cli::command();
+        let help = cmd.render_long_help().to_string();
+        assert!(help.contains("training_max.toml"));
+        assert!(help.contains("--config"));
     }
 
     #[test]
-    fn validate_required_assistance_training_max_for_primary_lift() {
+    fn supplemental_config_is_optional_and_cli_overrides_it() {
         let config = "[default]
 squat = 325
 bench_press = 235
 deadlift = 365
 overhead_press = 170";
-        let training_maxes = parse_training_maxes_from_str(config, "training_max.toml").unwrap();
-        let err = validate_required_assistance_training_max(&Lift::Squat, &training_maxes).unwrap_err();
+        let from_config = Config::parse(config, "training_max.toml")
+            .unwrap()
+            .supplemental(&Lift::Squat)
+            .unwrap();
+        assert_eq!(from_config, SupplementalChoice::default());
+        assert_eq!(from_config.resolve(), None);
+
+        let config = format!(
+            "{}\n\n[supplemental]\ntemplate = \"bbb\"\nbbb_percent = \"60\"\nscheme = \"3x8\"",
+            config
+        );
+        let from_config = Config::parse(&config, "training_max.toml")
+            .unwrap()
+            .supplemental(&Lift::Squat)
+            .unwrap();
         assert_eq!(
-            err,
-            WorkoutError::MissingTrainingMax {
-                lift: Lift::PowerClean
-            }
+            from_config.resolve(),
+            Some(Supplemental::BoringButBig(BbbPercent::Sixty))
+        );
+
+        let args = Cli::parse_from([
+            "five-three-one",
+            "-l",
+            "squat",
+            "-n",
+            "1",
+            "--supplemental",
+            "fsl",
+        ]);
+        assert_eq!(
+            args.supplemental_choice().or(from_config).resolve(),
+            Some(Supplemental::FirstSetLast(SetsReps { sets: 3, reps: 8 }))
+        );
+
+        let args = Cli::parse_from([
+            "five-three-one",
+            "-l",
+            "squat",
+            "-n",
+            "1",
+            "--supplemental",
+            "ssl",
+        ]);
+        assert_eq!(
+            args.supplemental_choice().resolve(),
+            Some(Supplemental::SecondSetLast(SetsReps { sets: 5, reps: 5 }))
         );
     }
 
     #[test]
-    fn parse_training_maxes_rejects_invalid_values_and_unknown_lifts() {
-        let missing_primary = "[default]
-squat = 325
-bench_press = 235
-deadlift = 365
-overhead_press = 170
-fakelift = 200";
-        assert!(parse_training_maxes_from_str(missing_primary, "training_max.toml").is_err());
-
-        let negative = "[default]
-squat = -325
-bench_press = 235
-deadlift = 365
-overhead_press = 170";
-        assert!(parse_training_maxes_from_str(negative, "training_max.toml").is_err());
+    fn cli_cycle_requires_block() {
+        assert!(
+            Cli::try_parse_from(["five-three-one", "-l", "squat", "-n", "1", "--cycle", "2"])
+                .is_err()
+        );
+        let args = Cli::parse_from([
+            "five-three-one",
+            "-l",
+            "squat",
+            "-n",
+            "7",
+            "--block",
+            "leader",
+        ]);
+        assert_eq!(args.session.block, Some(BlockPhase::Leader));
+        assert_eq!(args.session.cycle, 1);
+        assert_eq!(args.session.week, Some(Week::Week7(SeventhWeek::Deload)));
     }
 
     #[test]
-    fn parse_training_maxes_from_file_uses_provided_path() {
+    fn progress_cli_increments_override_config_and_results_are_validated() {
         let config = "[default]
 squat = 325
 bench_press = 235
 deadlift = 365
-overhead_press = 170";
-        let path = write_temp_config(config);
-        let result = parse_training_maxes_from_str(config, "training_max.toml").unwrap();
-
-        let from_disk = load_training_maxes_from_file(&path).unwrap();
-        fs::remove_file(&path).unwrap();
+overhead_press = 170
 
-        assert_eq!(result, from_disk);
-        assert_eq!(from_disk.get(&Lift::Squat), Some(&325));
+[progression]
+upper_body = 10
+lower_body = -400
+";
+        let args =
+            match Cli::parse_from(["five-three-one", "progress", "--upper-body", "2"]).command {
+                Some(Command::Progress(args)) => args,
+                _ => panic!("expected the progress command"),
+            };
+        let progression = Config::parse(config, "training_max.toml")
+            .unwrap()
+            .progression(args.upper_body, args.lower_body)
+            .unwrap();
+        assert_eq!(progression.upper_body, 2.0);
+        assert_eq!(progression.lower_body, -400.0);
+
+        let err = progress_training_maxes(config, "training_max.toml", &progression).unwrap_err();
+        assert!(err.to_string().contains("must be a positive number"));
+
+        let unknown = format!("{}fakelift = 5", config);
+        let err = Config::parse(&unknown, "training_max.toml")
+            .unwrap()
+            .progression(args.upper_body, args.lower_body)
+            .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Unknown lift 'fakelift' in [progression]"));
     }
 
     #[test]
-    fn cli_parses_defaults_and_config_option_is_optional() {
-        let args = Cli::parse_from(["five-three-one", "--primary-lift", "squat", "--week", "1"]);
-        assert_eq!(args.primary_lift, Lift::Squat);
-        assert_eq!(args.week, Week::Week1);
-        assert!(args.config_path.is_none());
+    fn log_command_takes_session_options_and_amrap_reps() {
+        let args = Cli::parse_from([
+            "five-three-one",
+            "log",
+            "-l",
+            "bp",
+            "-n",
+            "3",
+            "--amrap-reps",
+            "6",
+            "--date",
+            "2025-03-01",
+        ]);
+        let log_args = match args.command {
+            Some(Command::Log(log_args)) => log_args,
+            _ => panic!("expected the log command"),
+        };
+        assert_eq!(log_args.session.primary_lift, Some(Lift::BenchPress));
+        assert_eq!(log_args.amrap_reps, vec![6]);
+        assert_eq!(args.date, NaiveDate::from_ymd_opt(2025, 3, 1));
+
+        assert!(Cli::try_parse_from(["five-three-one", "log", "-n", "3"]).is_err());
+        assert!(parse_date("03/01/2025").is_err());
     }
 
     #[test]
-    fn help_output_documents_training_max_toml_path() {
-        let mut cmd = Cli::command();
-        let help = cmd.render_long_help().to_string();
-        assert!(help.contains("training_max.toml"));
-        assert!(help.contains("--config"));
+    fn cycle_command_takes_program_options_and_session_flags() {
+        let args = Cli::parse_from([
+            "five-three-one",
+            "cycle",
+            "--variant",
+            "5s-pro",
+            "--supplemental",
+            "bbb",
+            "--seed",
+            "7",
+        ]);
+        let cycle_args = match &args.command {
+            Some(Command::Cycle(cycle_args)) => cycle_args,
+            _ => panic!("expected the cycle command"),
+        };
+        assert_eq!(cycle_args.program.variant, Some(PrimaryVariant::FivesPro));
+        assert_eq!(args.supplemental, Some(SupplementalTemplate::BoringButBig));
+        assert_eq!(args.seed, Some(7));
+        assert!(
+            Cli::try_parse_from(["five-three-one", "cycle", "--primary-lift", "squat"]).is_err()
+        );
     }
 
     #[test]
-    fn parse_training_maxes_rejects_config_without_primary_keys() {
-        let config = "[default]\nsquat = 325
+    fn days_option_overrides_config_schedule() {
+        let two_day = "[default]
+squat = 325
 bench_press = 235
-front_squat = 215";
-        let err = parse_training_maxes_from_str(config, "training_max.toml").unwrap_err();
-        let msg = err.to_string();
-        assert!(msg.contains("deadlift"));
-        assert!(msg.contains("overhead press"));
-        assert!(msg.contains("Missing required primary lift training max"));
+deadlift = 365
+overhead_press = 170
+
+[schedule]
+days = 2
+";
+        let config = Config::parse(two_day, "training_max.toml").unwrap();
+        let args = Cli::parse_from(["five-three-one", "schedule"]);
+        assert_eq!(resolve_schedule(&args, &config).unwrap(), Schedule::TwoDay);
+        let args = Cli::parse_from([
+            "five-three-one",
+            "schedule",
+            "--days",
+            "3",
+            "--session",
+            "7",
+        ]);
+        assert_eq!(
+            resolve_schedule(&args, &config).unwrap(),
+            Schedule::ThreeDayRolling
+        );
+        assert!(Cli::try_parse_from(["five-three-one", "schedule", "--session", "0"]).is_err());
+    }
+
+    #[test]
+    fn e1rm_formula_and_units_options_are_validated() {
+        let args = Cli::parse_from([
+            "five-three-one",
+            "log",
+            "-l",
+            "s",
+            "-n",
+            "1",
+            "--e1rm-formula",
+            "lombardi",
+        ]);
+        assert_eq!(args.e1rm_formula, Some(E1rmFormula::Lombardi));
+        assert!(Cli::try_parse_from([
+            "five-three-one",
+            "-l",
+            "s",
+            "-n",
+            "1",
+            "--e1rm-formula",
+            "guess"
+        ])
+        .is_err());
+
+        let args = Cli::parse_from(["five-three-one", "-l", "s", "-n", "1", "--units", "lb"]);
+        assert_eq!(args.display_unit, Some(DisplayUnit::Pound));
+        assert!(
+            Cli::try_parse_from(["five-three-one", "-l", "s", "-n", "1", "--units", "stone"])
+                .is_err()
+        );
     }
 }
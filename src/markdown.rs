@@ -0,0 +1,180 @@
+//! Markdown rendering of workouts, with a table of sets and a task list to tick
+//! off.
+
+use crate::lifts::SetGroup;
+use crate::plates::PlateInventory;
+use crate::units::Units;
+use crate::workout::{Exercise, Section, Workout};
+
+/// Heading of `level` (1 for `#`), followed by a blank line
+pub fn heading(level: usize, text: &str) -> String {
+    format!("{} {}\n\n", "#".repeat(level), text)
+}
+
+/// Keeps a table cell's text from ending the cell early.
+fn escape_cell(text: &str) -> String {
+    text.replace('|', "\\|")
+}
+
+/// Notes shown for a set: its own, e.g. "joker, optional", then the
+/// exercise's, e.g. the reps to beat the best e1RM
+fn notes(set_group: &SetGroup, note: Option<&String>) -> String {
+    let notes: Vec<&str> = set_group
+        .note
+        .into_iter()
+        .chain(note.map(|note| note.as_str()))
+        .collect();
+    notes.join("; ")
+}
+
+/// Table of a section's sets, with a plates column when `plates` is given
+fn sets_table(section: &Section, units: &Units, plates: Option<&PlateInventory>) -> String {
+    let mut s = String::new();
+    match plates {
+        Some(_) => {
+            s += "| Lift | Weight | Sets | Reps | Plates | Notes |\n";
+            s += "| --- | ---: | ---: | ---: | --- | --- |\n";
+        }
+        None => {
+            s += "| Lift | Weight | Sets | Reps | Notes |\n";
+            s += "| --- | ---: | ---: | ---: | --- |\n";
+        }
+    }
+    for exercise in section.exercises.iter() {
+        let (set_group, note) = match exercise {
+            Exercise::Sets { set_group, note } => (set_group, note),
+            Exercise::Text(_) => continue,
+        };
+        // "5", "3-5" or "5+"
+        let mut reps = set_group.reps.to_string();
+        if let Some(max_reps) = set_group.max_reps {
+            reps += &format!("-{}", max_reps);
+        }
+        if set_group.amrap {
+            reps += "+";
+        }
+        s += &format!(
+            "| {} | {} | {} | {} |",
+            set_group.lift,
+            units.format(set_group.weight),
+            set_group.sets,
+            reps
+        );
+        if let Some(plates) = plates {
+            s += &format!(" {} |", set_group.plates(plates));
+        }
+        s += &format!(" {} |\n", escape_cell(&notes(set_group, note.as_ref())));
+    }
+    s
+}
+
+/// Task list with a checkbox for every set, then every other exercise
+fn task_list(section: &Section, units: &Units) -> String {
+    let mut s = String::new();
+    for exercise in section.exercises.iter() {
+        match exercise {
+            Exercise::Sets { set_group, .. } if set_group.sets > 1 => {
+                let one_set = SetGroup {
+                    sets: 1,
+                    ..set_group.clone()
+                };
+                let set = one_set.to_string_with_weight(&units.format(set_group.weight));
+                for number in 1..=set_group.sets {
+                    s += &format!("- [ ] {} (set {} of {})\n", set, number, set_group.sets);
+                }
+            }
+            Exercise::Sets { set_group, .. } => {
+                s += &format!(
+                    "- [ ] {}\n",
+                    set_group.to_string_with_weight(&units.format(set_group.weight))
+                );
+            }
+            Exercise::Text(text) => s += &format!("- [ ] {}\n", text),
+        }
+    }
+    s
+}
+
+/// Renders a workout under a heading of `level`, with a heading one level down
+/// for each section. A section's sets are listed in a table, followed by a task
+/// list with a checkbox for each set and exercise.
+pub fn render_workout(
+    workout: &Workout,
+    level: usize,
+    units: &Units,
+    plates: Option<&PlateInventory>,
+) -> String {
+    let mut s = heading(level, &workout.title());
+    for section in workout.sections.iter() {
+        s += &heading(level + 1, &section.name);
+        if section
+            .exercises
+            .iter()
+            .any(|exercise| matches!(exercise, Exercise::Sets { .. }))
+        {
+            s += &sets_table(section, units, plates);
+            s += "\n";
+        }
+        s += &task_list(section, units);
+        s += "\n";
+    }
+    s
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lifts::{generate_supplemental_sets, BbbPercent, Lift, Supplemental, Week};
+    use crate::rounding::RoundingRules;
+    use crate::scheme::Scheme;
+    use std::collections::HashMap;
+
+    #[test]
+    fn renders_tables_and_a_checkbox_per_set() {
+        let mut training_maxes = HashMap::new();
+        training_maxes.insert(Lift::Squat, 325.0);
+        let bbb = generate_supplemental_sets(
+            &Lift::Squat,
+            &Week::Week1,
+            &Supplemental::BoringButBig(BbbPercent::Fifty),
+            &Scheme::default(),
+            &RoundingRules::default(),
+            &training_maxes,
+        )
+        .unwrap();
+        let mut supplemental = Section::new("Supplemental");
+        supplemental
+            .exercises
+            .extend(bbb.into_iter().map(|set_group| Exercise::Sets {
+                set_group,
+                note: Some("belt | straps".to_owned()),
+            }));
+        let mut core = Section::new("Core");
+        core.exercises
+            .push(Exercise::Text("bird dog, 3x10/side".to_owned()));
+        let workout = Workout {
+            primary_lift: Lift::Squat,
+            week: Week::Week1,
+            sections: vec![supplemental, core],
+        };
+
+        assert_eq!(
+            render_workout(&workout, 2, &Units::default(), Some(&PlateInventory::default())),
+            "## squat week 1\n\n\
+             ### Supplemental\n\n\
+             | Lift | Weight | Sets | Reps | Plates | Notes |\n\
+             | --- | ---: | ---: | ---: | --- | --- |\n\
+             | squat | 163 | 5 | 10 | 45 10 2.5 per side; not loadable, closest is 160 | belt \\| straps |\n\
+             \n\
+             - [ ] squat 163 x10 (set 1 of 5)\n\
+             - [ ] squat 163 x10 (set 2 of 5)\n\
+             - [ ] squat 163 x10 (set 3 of 5)\n\
+             - [ ] squat 163 x10 (set 4 of 5)\n\
+             - [ ] squat 163 x10 (set 5 of 5)\n\
+             \n\
+             ### Core\n\n\
+             - [ ] bird dog, 3x10/side\n\
+             \n"
+        );
+    }
+}
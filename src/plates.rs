@@ -0,0 +1,243 @@
+//! Plate inventories and the plates to load on each side of the bar for a set.
+
+use std::fmt;
+
+use crate::lifts::SetGroup;
+use crate::units::Unit;
+
+/// Smallest difference between two weights that is treated as a real difference
+const TOLERANCE: f32 = 0.001;
+
+/// Bar and plates available to load a barbell
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlateInventory {
+    /// Weight of the empty bar
+    pub bar: f32,
+    /// Plate weights and how many pairs of each are available, heaviest first
+    pairs: Vec<(f32, u16)>,
+}
+
+impl Default for PlateInventory {
+    /// A 45 lb bar and a typical gym's pound plates
+    fn default() -> Self {
+        PlateInventory::for_unit(Unit::Pound)
+    }
+}
+
+impl PlateInventory {
+    /// A typical gym's bar and plates in `unit`: a 45 lb bar with 45/35/25/10/5/2.5
+    /// lb plates, or a 20 kg bar with 25/20/15/10/5/2.5/1.25/0.5 kg plates.
+    pub fn for_unit(unit: Unit) -> PlateInventory {
+        match unit {
+            Unit::Pound => PlateInventory::new(
+                45.0,
+                vec![
+                    (45.0, 8),
+                    (35.0, 2),
+                    (25.0, 2),
+                    (10.0, 2),
+                    (5.0, 2),
+                    (2.5, 2),
+                ],
+            ),
+            Unit::Kilogram => PlateInventory::new(
+                20.0,
+                vec![
+                    (25.0, 8),
+                    (20.0, 2),
+                    (15.0, 2),
+                    (10.0, 2),
+                    (5.0, 2),
+                    (2.5, 2),
+                    (1.25, 2),
+                    (0.5, 2),
+                ],
+            ),
+        }
+    }
+
+    /// An inventory of `bar` and `pairs` of plates, given as plate weight and
+    /// number of pairs in any order
+    pub fn new(bar: f32, mut pairs: Vec<(f32, u16)>) -> PlateInventory {
+        pairs.sort_by(|a, b| b.0.total_cmp(&a.0));
+        PlateInventory { bar, pairs }
+    }
+
+    /// Plates to put on each side of the bar for `weight`, heaviest first. When
+    /// the weight can't be loaded exactly, the breakdown is for the heaviest
+    /// loadable weight below it.
+    pub fn load(&self, weight: f32) -> PlateBreakdown {
+        let mut search = LoadSearch {
+            target: (weight - self.bar) / 2.0,
+            per_side: vec![],
+            best: vec![],
+            best_total: 0.0,
+        };
+        search.run(&self.pairs, 0.0);
+
+        PlateBreakdown {
+            weight,
+            loaded: self.bar + 2.0 * search.best_total,
+            per_side: search.best,
+        }
+    }
+}
+
+/// Depth-first search for the heaviest load per side that doesn't go over
+/// `target`. The inventory is small, so every combination of plate counts can be
+/// tried; the search stops as soon as the target is loaded exactly.
+struct LoadSearch {
+    target: f32,
+    /// Plates of the combination being tried
+    per_side: Vec<f32>,
+    best: Vec<f32>,
+    best_total: f32,
+}
+
+impl LoadSearch {
+    fn is_exact(&self) -> bool {
+        self.best_total > self.target - TOLERANCE
+    }
+
+    /// Tries every count of the first plate in `pairs`, most first, then the
+    /// remaining plates. Heavier plates are tried first, so of two equally heavy
+    /// loads the one with the heavier plates is kept.
+    fn run(&mut self, pairs: &[(f32, u16)], total: f32) {
+        if total > self.best_total + TOLERANCE {
+            self.best_total = total;
+            self.best = self.per_side.clone();
+        }
+        let Some(((plate, available), rest)) = pairs.split_first() else {
+            return;
+        };
+        // give up on combinations that can't beat the best load so far
+        let most_left: f32 = pairs
+            .iter()
+            .map(|(plate, pairs)| plate * *pairs as f32)
+            .sum();
+        if self.is_exact() || total + most_left < self.best_total + TOLERANCE {
+            return;
+        }
+
+        let fits = ((self.target - total + TOLERANCE) / plate).floor().max(0.0) as u16;
+        for count in (0..=fits.min(*available)).rev() {
+            let len = self.per_side.len();
+            self.per_side
+                .extend(std::iter::repeat_n(*plate, count as usize));
+            self.run(rest, total + plate * count as f32);
+            self.per_side.truncate(len);
+            if self.is_exact() {
+                return;
+            }
+        }
+    }
+}
+
+/// Plates loaded on each side of the bar for a set
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlateBreakdown {
+    /// Weight the set calls for
+    pub weight: f32,
+    /// Weight of the bar as loaded; less than `weight` if it isn't loadable
+    pub loaded: f32,
+    /// Plates to load on each side, heaviest first
+    pub per_side: Vec<f32>,
+}
+
+impl PlateBreakdown {
+    /// Whether the plates load the weight the set calls for
+    pub fn is_exact(&self) -> bool {
+        (self.weight - self.loaded).abs() < TOLERANCE
+    }
+}
+
+impl fmt::Display for PlateBreakdown {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.per_side.is_empty() {
+            write!(f, "empty bar")?;
+        } else {
+            let plates: Vec<String> = self
+                .per_side
+                .iter()
+                .map(|plate| plate.to_string())
+                .collect();
+            write!(f, "{} per side", plates.join(" "))?;
+        }
+        if !self.is_exact() {
+            write!(f, "; not loadable, closest is {}", self.loaded)?;
+        }
+        Ok(())
+    }
+}
+
+impl SetGroup {
+    /// Plates to load on each side of the bar for this set's weight
+    pub fn plates(&self, inventory: &PlateInventory) -> PlateBreakdown {
+        inventory.load(self.weight)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn loads_heaviest_plates_first() {
+        let inventory = PlateInventory::default();
+        let breakdown = inventory.load(275.0);
+        assert_eq!(breakdown.per_side, vec![45.0, 45.0, 25.0]);
+        assert!(breakdown.is_exact());
+        assert_eq!(breakdown.to_string(), "45 45 25 per side");
+        assert_eq!(inventory.load(45.0).to_string(), "empty bar");
+    }
+
+    #[test]
+    fn finds_exact_loads_that_heaviest_first_misses() {
+        // taking the 45 first leaves 15 that the 35 and 25 can't make up
+        let inventory = PlateInventory::new(45.0, vec![(45.0, 1), (35.0, 1), (25.0, 1)]);
+        let breakdown = inventory.load(165.0);
+        assert_eq!(breakdown.per_side, vec![35.0, 25.0]);
+        assert!(breakdown.is_exact());
+
+        // when nothing is exact, the heaviest load under the weight wins
+        let breakdown = inventory.load(175.0);
+        assert_eq!(
+            breakdown.to_string(),
+            "35 25 per side; not loadable, closest is 165"
+        );
+    }
+
+    #[test]
+    fn warns_when_weight_is_not_loadable() {
+        let inventory = PlateInventory::default();
+        let breakdown = inventory.load(163.0);
+        assert!(!breakdown.is_exact());
+        assert_eq!(breakdown.loaded, 160.0);
+        assert_eq!(
+            breakdown.to_string(),
+            "45 10 2.5 per side; not loadable, closest is 160"
+        );
+
+        let light = inventory.load(40.0);
+        assert_eq!(light.to_string(), "empty bar; not loadable, closest is 45");
+    }
+
+    #[test]
+    fn kilogram_inventory_has_a_20_kg_bar_and_change_plates() {
+        let inventory = PlateInventory::for_unit(Unit::Kilogram);
+        assert_eq!(inventory.bar, 20.0);
+        let breakdown = inventory.load(101.0);
+        assert_eq!(breakdown.to_string(), "25 15 0.5 per side");
+        assert!(breakdown.is_exact());
+    }
+
+    #[test]
+    fn uses_fractional_plates_and_respects_pair_counts() {
+        let inventory = PlateInventory::new(20.0, vec![(1.25, 1), (20.0, 1), (10.0, 2), (2.5, 1)]);
+        assert_eq!(inventory.load(62.5).per_side, vec![20.0, 1.25]);
+        // only one pair of 20s, so the rest comes from 10s
+        let breakdown = inventory.load(102.5);
+        assert_eq!(breakdown.per_side, vec![20.0, 10.0, 10.0, 1.25]);
+        assert!(breakdown.is_exact());
+    }
+}
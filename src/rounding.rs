@@ -0,0 +1,168 @@
+//! Rounding of scaled weights to weights that can be loaded on the bar.
+
+use std::collections::HashMap;
+use std::fmt;
+use strum_macros::EnumString;
+
+use crate::lifts::Lift;
+use crate::units::Unit;
+
+/// Keeps weights that are already a multiple of the increment from being
+/// pushed to the next one by floating point error
+const TOLERANCE: f32 = 0.0001;
+
+/// Direction a scaled weight is rounded in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumString)]
+pub enum RoundingMode {
+    /// To the nearest increment
+    #[strum(serialize = "nearest")]
+    Nearest,
+    /// To the increment below, never heavier than prescribed
+    #[strum(serialize = "down")]
+    Down,
+    /// To the increment above, never lighter than prescribed
+    #[strum(serialize = "up")]
+    Up,
+}
+
+impl fmt::Display for RoundingMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s: &str = match self {
+            RoundingMode::Nearest => "nearest",
+            RoundingMode::Down => "down",
+            RoundingMode::Up => "up",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+/// How scaled weights are rounded to a loadable weight
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rounding {
+    /// Weights are rounded to a multiple of this
+    pub increment: f32,
+    /// Direction weights are rounded in
+    pub mode: RoundingMode,
+}
+
+impl Default for Rounding {
+    /// Nearest whole pound
+    fn default() -> Self {
+        Rounding::for_unit(Unit::Pound)
+    }
+}
+
+impl Rounding {
+    /// Nearest whole pound, or nearest 2.5 kg (the smallest jump with a pair of
+    /// 1.25 kg plates)
+    pub fn for_unit(unit: Unit) -> Rounding {
+        let increment = match unit {
+            Unit::Pound => 1.0,
+            Unit::Kilogram => 2.5,
+        };
+        Rounding {
+            increment,
+            mode: RoundingMode::Nearest,
+        }
+    }
+
+    /// Rounds `weight` to a multiple of the increment.
+    pub fn round(&self, weight: f32) -> f32 {
+        let increments = weight / self.increment;
+        let increments = match self.mode {
+            RoundingMode::Nearest => increments.round(),
+            RoundingMode::Down => (increments + TOLERANCE).floor(),
+            RoundingMode::Up => (increments - TOLERANCE).ceil(),
+        };
+        clean(increments * self.increment)
+    }
+}
+
+/// Drops floating point noise below a thousandth so that weights such as
+/// 102.3 print as written
+pub fn clean(weight: f32) -> f32 {
+    (weight * 1000.0).round() / 1000.0
+}
+
+/// Rounding for every lift, with per-lift overrides
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RoundingRules {
+    /// Rounding for lifts without their own
+    pub default: Rounding,
+    /// Per-lift rounding, overriding `default`
+    pub lifts: HashMap<Lift, Rounding>,
+}
+
+impl RoundingRules {
+    /// The unit's default rounding for every lift
+    pub fn for_unit(unit: Unit) -> RoundingRules {
+        RoundingRules {
+            default: Rounding::for_unit(unit),
+            lifts: HashMap::new(),
+        }
+    }
+
+    /// Rounding for `lift`: its own, or the default.
+    pub fn for_lift(&self, lift: &Lift) -> &Rounding {
+        self.lifts.get(lift).unwrap_or(&self.default)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rounds_to_increment_in_each_mode() {
+        let nearest = Rounding {
+            increment: 5.0,
+            mode: RoundingMode::Nearest,
+        };
+        let down = Rounding {
+            mode: RoundingMode::Down,
+            ..nearest
+        };
+        let up = Rounding {
+            mode: RoundingMode::Up,
+            ..nearest
+        };
+        assert_eq!(nearest.round(162.5), 165.0);
+        assert_eq!(nearest.round(211.25), 210.0);
+        assert_eq!(down.round(164.9), 160.0);
+        assert_eq!(up.round(160.1), 165.0);
+        assert_eq!(down.round(165.0), 165.0);
+        assert_eq!(up.round(165.0), 165.0);
+        assert_eq!(Rounding::default().round(162.5), 163.0);
+    }
+
+    #[test]
+    fn rounds_to_fractional_increments() {
+        let kg = Rounding::for_unit(Unit::Kilogram);
+        assert_eq!(kg.round(66.3), 67.5);
+        assert_eq!(kg.round(91.0), 90.0);
+        let micro = Rounding {
+            increment: 0.1,
+            mode: RoundingMode::Down,
+        };
+        assert_eq!(micro.round(102.37).to_string(), "102.3");
+        let quarter = Rounding {
+            increment: 1.25,
+            mode: RoundingMode::Up,
+        };
+        assert_eq!(quarter.round(101.0), 101.25);
+    }
+
+    #[test]
+    fn per_lift_rounding_overrides_default() {
+        let mut rules = RoundingRules::default();
+        rules.lifts.insert(
+            Lift::OverheadPress,
+            Rounding {
+                increment: 2.0,
+                mode: RoundingMode::Down,
+            },
+        );
+        assert_eq!(rules.for_lift(&Lift::OverheadPress).round(111.0), 110.0);
+        assert_eq!(rules.for_lift(&Lift::Squat).round(111.4), 111.0);
+    }
+}
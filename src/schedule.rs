@@ -0,0 +1,171 @@
+//! Training schedules: which primary lifts are done on each training day.
+
+use std::fmt;
+use strum_macros::EnumString;
+
+use crate::lifts::{Lift, Week};
+
+/// 5/3/1 weeks in a cycle, in order
+const CYCLE_WEEKS: [Week; 4] = [Week::Week1, Week::Week2, Week::Week3, Week::Week4];
+
+/// How the primary lifts are spread over training days
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, EnumString)]
+pub enum Schedule {
+    /// Four days a week, one primary lift per day
+    #[default]
+    #[strum(serialize = "4", serialize = "4-day", serialize = "four-day")]
+    FourDay,
+    /// Three days a week, one primary lift per day, rotating through the lifts
+    /// so each 5/3/1 week spans more than a calendar week
+    #[strum(serialize = "3", serialize = "3-day", serialize = "three-day")]
+    ThreeDayRolling,
+    /// Two days a week, two primary lifts per day
+    #[strum(serialize = "2", serialize = "2-day", serialize = "two-day")]
+    TwoDay,
+}
+
+impl fmt::Display for Schedule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s: &str = match self {
+            Schedule::FourDay => "4-day",
+            Schedule::ThreeDayRolling => "3-day rolling",
+            Schedule::TwoDay => "2-day",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+impl Schedule {
+    /// Number of training days in a calendar week
+    pub fn days_per_week(&self) -> u32 {
+        match self {
+            Schedule::FourDay => 4,
+            Schedule::ThreeDayRolling => 3,
+            Schedule::TwoDay => 2,
+        }
+    }
+
+    fn lifts_per_session(&self) -> usize {
+        match self {
+            Schedule::FourDay | Schedule::ThreeDayRolling => 1,
+            Schedule::TwoDay => 2,
+        }
+    }
+
+    /// Sessions it takes to do every 5/3/1 week of a cycle for every primary lift
+    pub fn sessions_per_cycle(&self) -> u32 {
+        (CYCLE_WEEKS.len() * Lift::PRIMARY_LIFTS.len() / self.lifts_per_session()) as u32
+    }
+
+    /// The `number`th session since starting the schedule, counting from 1, or
+    /// `None` for 0. Sessions past the end of a cycle carry on into the next one.
+    pub fn session(&self, number: u32) -> Option<ScheduledSession> {
+        let index = number.checked_sub(1)?;
+        let lifts_per_session = self.lifts_per_session();
+        let sessions_per_week = (Lift::PRIMARY_LIFTS.len() / lifts_per_session) as u32;
+
+        let first_lift = (index % sessions_per_week) as usize * lifts_per_session;
+        Some(ScheduledSession {
+            number,
+            cycle: index / self.sessions_per_cycle() + 1,
+            calendar_week: index / self.days_per_week() + 1,
+            day: index % self.days_per_week() + 1,
+            week: CYCLE_WEEKS[(index / sessions_per_week) as usize % CYCLE_WEEKS.len()],
+            lifts: Lift::PRIMARY_LIFTS[first_lift..first_lift + lifts_per_session].to_vec(),
+        })
+    }
+}
+
+/// Where a session falls in the schedule and the work it calls for
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScheduledSession {
+    /// 1-based session number since starting the schedule
+    pub number: u32,
+    /// 1-based cycle the session belongs to
+    pub cycle: u32,
+    /// 1-based calendar week since starting the schedule
+    pub calendar_week: u32,
+    /// 1-based training day within the calendar week
+    pub day: u32,
+    /// 5/3/1 week of the cycle for the session's lifts
+    pub week: Week,
+    /// Primary lifts done in the session
+    pub lifts: Vec<Lift>,
+}
+
+impl fmt::Display for ScheduledSession {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let lifts: Vec<String> = self.lifts.iter().map(|lift| lift.to_string()).collect();
+        write!(
+            f,
+            "Session {} (week {}, day {}): {}, cycle {} week {}",
+            self.number,
+            self.calendar_week,
+            self.day,
+            lifts.join(" + "),
+            self.cycle,
+            self.week.number()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn four_day_schedule_does_one_lift_per_day() {
+        let schedule = Schedule::default();
+        assert_eq!(schedule.sessions_per_cycle(), 16);
+        assert_eq!(schedule.session(0), None);
+        let session = schedule.session(7).unwrap();
+        assert_eq!(session.lifts, vec![Lift::Deadlift]);
+        assert_eq!(session.week, Week::Week2);
+        assert_eq!((session.calendar_week, session.day), (2, 3));
+        assert_eq!(
+            session.to_string(),
+            "Session 7 (week 2, day 3): deadlift, cycle 1 week 2"
+        );
+    }
+
+    #[test]
+    fn three_day_schedule_rolls_lifts_across_weeks() {
+        let schedule = Schedule::from_str("3").unwrap();
+        assert_eq!(schedule, Schedule::ThreeDayRolling);
+        // the 4th session starts calendar week 2 with the last lift of 5/3/1 week 1
+        let session = schedule.session(4).unwrap();
+        assert_eq!(session.lifts, vec![Lift::OverheadPress]);
+        assert_eq!(session.week, Week::Week1);
+        assert_eq!((session.calendar_week, session.day), (2, 1));
+
+        let last = schedule.session(16).unwrap();
+        assert_eq!(
+            (last.lifts[0], last.week, last.calendar_week, last.day),
+            (Lift::OverheadPress, Week::Week4, 6, 1)
+        );
+        let next_cycle = schedule.session(17).unwrap();
+        assert_eq!(
+            (next_cycle.cycle, next_cycle.week, next_cycle.lifts[0]),
+            (2, Week::Week1, Lift::Squat)
+        );
+    }
+
+    #[test]
+    fn two_day_schedule_pairs_lifts() {
+        let schedule = Schedule::TwoDay;
+        assert_eq!(schedule.sessions_per_cycle(), 8);
+        assert_eq!(
+            schedule.session(1).unwrap().lifts,
+            vec![Lift::Squat, Lift::BenchPress]
+        );
+        let session = schedule.session(6).unwrap();
+        assert_eq!(session.lifts, vec![Lift::Deadlift, Lift::OverheadPress]);
+        assert_eq!(session.week, Week::Week3);
+        assert_eq!(
+            session.to_string(),
+            "Session 6 (week 3, day 2): deadlift + overhead press, cycle 1 week 3"
+        );
+        assert!(Schedule::from_str("5").is_err());
+    }
+}
@@ -0,0 +1,343 @@
+//! Percentage schemes: the percent of training max and reps of every set in a
+//! cycle.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::lifts::{Lift, SeventhWeek, Week};
+use crate::warm_up::{WarmUp, WarmUpRules};
+
+/// A single prescribed set, as a fraction of training max
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SetScheme {
+    /// Fraction of training max, e.g. 0.85 for 85%
+    pub scalar: f32,
+    /// Reps per set, or the least reps for a rep range
+    pub reps: i8,
+    /// Upper end of a rep range, e.g. 5 for "3-5"
+    pub max_reps: Option<i8>,
+    /// Whether the set is done for as many reps as possible
+    pub amrap: bool,
+}
+
+impl SetScheme {
+    /// A set at `scalar` of training max for `reps`, without a rep range
+    pub fn new(scalar: f32, reps: i8, amrap: bool) -> SetScheme {
+        SetScheme {
+            scalar,
+            reps,
+            max_reps: None,
+            amrap,
+        }
+    }
+}
+
+/// Parses sets written as percent of training max and reps, e.g. "85x5+", "80x3-5", or "62.5x5".
+impl FromStr for SetScheme {
+    type Err = String;
+
+    fn from_str(src: &str) -> Result<Self, Self::Err> {
+        let err = || {
+            format!("Invalid set '{src}'. Expected percent x reps, e.g. 65x5, 80x3-5, or 85x5+.")
+        };
+        let (percent, reps) = src.split_once('x').ok_or_else(err)?;
+        let percent: f32 = percent.trim().parse().map_err(|_| err())?;
+        if !(percent > 0.0 && percent <= 200.0) {
+            return Err(err());
+        }
+
+        let reps = reps.trim();
+        let (reps, amrap) = match reps.strip_suffix('+') {
+            Some(reps) => (reps, true),
+            None => (reps, false),
+        };
+        let (reps, max_reps) = match reps.split_once('-') {
+            Some((min, max)) => (min, Some(max.parse::<i8>().map_err(|_| err())?)),
+            None => (reps, None),
+        };
+        let reps: i8 = reps.parse().map_err(|_| err())?;
+        if reps <= 0 || max_reps.is_some_and(|max_reps| max_reps <= reps) {
+            return Err(err());
+        }
+
+        Ok(SetScheme {
+            scalar: percent / 100.0,
+            reps,
+            max_reps,
+            amrap,
+        })
+    }
+}
+
+impl fmt::Display for SetScheme {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}x{}", self.scalar * 100.0, self.reps)?;
+        if let Some(max_reps) = self.max_reps {
+            write!(f, "-{}", max_reps)?;
+        }
+        if self.amrap {
+            write!(f, "+")?;
+        }
+        Ok(())
+    }
+}
+
+/// Sets done in one week of a cycle
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct WeekScheme {
+    /// Primary lift warm-up sets done instead of the lift's warm-up ramp, if given
+    pub warm_up: Option<Vec<SetScheme>>,
+    /// Primary lift working sets, heaviest last
+    pub working: Vec<SetScheme>,
+    /// Big assistance sets
+    pub assistance: Vec<SetScheme>,
+    /// Big assistance sets for explosive lifts such as power cleans
+    pub explosive_assistance: Vec<SetScheme>,
+}
+
+/// Percentages and reps for every week of a cycle, interpreted by the set generators
+#[derive(Debug, Clone, PartialEq)]
+pub struct Scheme {
+    weeks: HashMap<Week, WeekScheme>,
+    warm_up: WarmUpRules,
+    /// Training max fraction added for each successive Joker set
+    joker_increment: f32,
+}
+
+/// Joker sets go up 5% of training max at a time unless asked otherwise
+pub const DEFAULT_JOKER_INCREMENT: f32 = 0.05;
+
+/// Names of the schemes that don't need to be defined in the config file
+pub const BUILT_IN_SCHEMES: [&str; 2] = ["531", "351"];
+
+/// Every week a scheme defines
+pub const SCHEME_WEEKS: [Week; 6] = [
+    Week::Week1,
+    Week::Week2,
+    Week::Week3,
+    Week::Week4,
+    Week::Week7(SeventhWeek::Deload),
+    Week::Week7(SeventhWeek::TmTest),
+];
+
+impl Default for Scheme {
+    fn default() -> Self {
+        Scheme::five_three_one()
+    }
+}
+
+impl Scheme {
+    /// Looks up a built-in scheme by name.
+    pub fn built_in(name: &str) -> Option<Scheme> {
+        match name {
+            "531" => Some(Scheme::five_three_one()),
+            "351" => Some(Scheme::three_five_one()),
+            _ => None,
+        }
+    }
+
+    /// Standard 5/3/1: 5s week, 3s week, 5/3/1 week, deload.
+    pub fn five_three_one() -> Scheme {
+        let set = SetScheme::new;
+
+        let fives = vec![set(0.65, 5, false), set(0.75, 5, false), set(0.85, 5, true)];
+        let threes = vec![set(0.7, 3, false), set(0.8, 3, false), set(0.9, 3, true)];
+        let five_three_one = vec![set(0.75, 5, false), set(0.85, 3, false), set(0.95, 1, true)];
+
+        let explosive = vec![
+            set(0.65, 3, false),
+            set(0.75, 3, false),
+            set(0.85, 3, false),
+        ];
+
+        let mut weeks = HashMap::new();
+        weeks.insert(
+            Week::Week1,
+            WeekScheme {
+                warm_up: None,
+                working: fives,
+                assistance: vec![
+                    set(0.5, 10, false),
+                    set(0.6, 10, false),
+                    set(0.7, 10, false),
+                ],
+                explosive_assistance: explosive.clone(),
+            },
+        );
+        weeks.insert(
+            Week::Week2,
+            WeekScheme {
+                warm_up: None,
+                working: threes,
+                assistance: vec![set(0.6, 8, false), set(0.7, 8, false), set(0.8, 6, false)],
+                explosive_assistance: explosive.clone(),
+            },
+        );
+        weeks.insert(
+            Week::Week3,
+            WeekScheme {
+                warm_up: None,
+                working: five_three_one,
+                assistance: vec![
+                    set(0.65, 5, false),
+                    set(0.75, 5, false),
+                    set(0.85, 5, false),
+                ],
+                explosive_assistance: explosive,
+            },
+        );
+        Scheme::insert_deloads(&mut weeks);
+
+        Scheme {
+            weeks,
+            warm_up: WarmUpRules::default(),
+            joker_increment: DEFAULT_JOKER_INCREMENT,
+        }
+    }
+
+    /// 5/3/1 with the 3s week done first: 3s week, 5s week, 5/3/1 week, deload.
+    /// Warm-ups and assistance move with the working sets they go with.
+    pub fn three_five_one() -> Scheme {
+        let mut scheme = Scheme::five_three_one();
+        let fives = scheme.weeks.remove(&Week::Week1).unwrap();
+        let threes = scheme.weeks.remove(&Week::Week2).unwrap();
+        scheme.weeks.insert(Week::Week1, threes);
+        scheme.weeks.insert(Week::Week2, fives);
+        scheme
+    }
+
+    fn insert_deloads(weeks: &mut HashMap<Week, WeekScheme>) {
+        let set = SetScheme::new;
+
+        weeks.insert(
+            Week::Week4,
+            WeekScheme {
+                warm_up: None,
+                working: vec![set(0.4, 5, false), set(0.5, 5, false), set(0.6, 5, false)],
+                assistance: vec![set(0.4, 5, false), set(0.5, 5, false), set(0.6, 5, false)],
+                explosive_assistance: vec![
+                    set(0.5, 3, false),
+                    set(0.6, 3, false),
+                    set(0.7, 3, false),
+                ],
+            },
+        );
+        weeks.insert(
+            Week::Week7(SeventhWeek::Deload),
+            WeekScheme {
+                working: vec![
+                    set(0.7, 5, false),
+                    SetScheme {
+                        max_reps: Some(5),
+                        ..set(0.8, 3, false)
+                    },
+                    set(0.9, 1, false),
+                    set(1.0, 1, false),
+                ],
+                ..weeks[&Week::Week4].clone()
+            },
+        );
+        weeks.insert(
+            Week::Week7(SeventhWeek::TmTest),
+            WeekScheme {
+                working: vec![
+                    set(0.7, 5, false),
+                    set(0.8, 5, false),
+                    set(0.9, 5, false),
+                    set(1.0, 5, false),
+                ],
+                ..weeks[&Week::Week4].clone()
+            },
+        );
+    }
+
+    /// Sets for the given week, if the scheme defines it.
+    pub fn week(&self, week: &Week) -> Option<&WeekScheme> {
+        self.weeks.get(week)
+    }
+
+    /// Replaces the sets for the given week.
+    pub fn set_week(&mut self, week: Week, sets: WeekScheme) {
+        self.weeks.insert(week, sets);
+    }
+
+    /// Warm-up ramp for `lift`, used in weeks that don't give their own warm-up sets.
+    pub fn warm_up(&self, lift: &Lift) -> &WarmUp {
+        self.warm_up.for_lift(lift)
+    }
+
+    /// Replaces the warm-up ramps.
+    pub fn set_warm_up(&mut self, warm_up: WarmUpRules) {
+        self.warm_up = warm_up;
+    }
+
+    /// Training max fraction each Joker set adds to the one before it.
+    pub fn joker_increment(&self) -> f32 {
+        self.joker_increment
+    }
+
+    /// Replaces the Joker set increment, e.g. 0.1 for 10% of training max.
+    pub fn set_joker_increment(&mut self, increment: f32) {
+        self.joker_increment = increment;
+    }
+
+    /// Reps for Joker sets after the given week's top set: triples after a
+    /// 3s top set, singles after a 1s top set, and none otherwise.
+    pub fn joker_reps(&self, week: &Week) -> Option<i8> {
+        self.week(week)?
+            .working
+            .last()
+            .filter(|top_set| top_set.amrap && top_set.reps <= 3)
+            .map(|top_set| top_set.reps)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn set_scheme_parses_percent_reps_ranges_and_amrap() {
+        assert_eq!(
+            SetScheme::from_str("85x5+"),
+            Ok(SetScheme::new(0.85, 5, true))
+        );
+        assert_eq!(
+            SetScheme::from_str("80x3-5"),
+            Ok(SetScheme {
+                max_reps: Some(5),
+                ..SetScheme::new(0.8, 3, false)
+            })
+        );
+        assert_eq!(SetScheme::from_str("62.5x5").unwrap().scalar, 0.625);
+        assert!(SetScheme::from_str("85").is_err());
+        assert!(SetScheme::from_str("0x5").is_err());
+        assert!(SetScheme::from_str("85x5-3").is_err());
+        assert_eq!(
+            SetScheme::from_str("80x3-5+").unwrap().to_string(),
+            "80x3-5+"
+        );
+    }
+
+    #[test]
+    fn three_five_one_swaps_first_two_weeks() {
+        let standard = Scheme::five_three_one();
+        let swapped = Scheme::three_five_one();
+        assert_eq!(swapped.week(&Week::Week1), standard.week(&Week::Week2));
+        assert_eq!(swapped.week(&Week::Week2), standard.week(&Week::Week1));
+        assert_eq!(swapped.week(&Week::Week3), standard.week(&Week::Week3));
+        assert_eq!(swapped.joker_reps(&Week::Week1), Some(3));
+        assert_eq!(swapped.joker_reps(&Week::Week2), None);
+    }
+
+    #[test]
+    fn built_in_schemes_define_every_week() {
+        for name in BUILT_IN_SCHEMES.iter() {
+            let scheme = Scheme::built_in(name).unwrap();
+            for week in SCHEME_WEEKS.iter() {
+                assert!(scheme.week(week).unwrap().working.len() >= 2);
+            }
+        }
+    }
+}
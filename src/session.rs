@@ -0,0 +1,318 @@
+//! A session's programming, resolved from the config file and options, and the
+//! whole workout it makes.
+
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::collections::HashMap;
+
+use crate::block::{BlockPhase, BlockPosition, PhaseTemplate};
+use crate::config::SupplementalChoice;
+use crate::e1rm::E1rmFormula;
+use crate::lifts::{
+    evaluate_tm_test, generate_assistance_sets, generate_primary_sets, generate_supplemental_sets,
+    Lift, PrimaryVariant, SeventhWeek, Supplemental, TmTestResult, Week, WorkoutError,
+};
+use crate::rounding::RoundingRules;
+use crate::scheme::Scheme;
+use crate::static_strings::{CORE_EXERCISES, LIMBER_11, WARM_UP};
+use crate::units::Units;
+use crate::workout::{Exercise, Section, Workout};
+
+/// A session's primary lift programming, after resolving command line options against
+/// the block and the config file
+#[derive(Debug, Clone)]
+pub struct Session {
+    /// Lift the session is built around
+    pub primary_lift: Lift,
+    /// Week of the cycle, with the 7th week protocol resolved
+    pub week: Week,
+    /// Where the session falls in a Leader/Anchor block, if it's part of one
+    pub position: Option<BlockPosition>,
+    /// Programming of the block phase at `position`
+    pub phase_template: Option<PhaseTemplate>,
+    /// How the primary lift's working sets are done
+    pub variant: PrimaryVariant,
+    /// Number of Joker sets after the AMRAP top set
+    pub jokers: u8,
+    /// Supplemental work after the primary lift, if any
+    pub supplemental: Option<Supplemental>,
+    /// Percentages and reps for the primary lift's sets
+    pub scheme: Scheme,
+    /// How scaled weights are rounded
+    pub rounding: RoundingRules,
+    /// Training max of every lift in the config file
+    pub training_maxes: HashMap<Lift, f32>,
+}
+
+/// What to program for a session, for [`Config::session`](crate::config::Config::session)
+/// to resolve against the Leader/Anchor block and the config file. Options
+/// left unset come from there.
+#[derive(Debug, Clone)]
+pub struct SessionOptions {
+    /// 7th week protocol for a `Week7` session
+    pub seventh_week: Option<SeventhWeek>,
+    /// Phase of a Leader/Anchor block the session is part of
+    pub block: Option<BlockPhase>,
+    /// 1-based cycle within the `block` phase
+    pub cycle: u8,
+    /// How the primary lift's working sets are done, in place of the block's
+    pub variant: Option<PrimaryVariant>,
+    /// Number of Joker sets after the AMRAP top set
+    pub jokers: u8,
+    /// Training max fraction each Joker set adds, in place of the scheme's
+    pub joker_increment: Option<f32>,
+    /// Name of the percentage scheme, in place of the config file's `[scheme]`
+    pub scheme: Option<String>,
+    /// Supplemental work, filled in from the block and then the config file
+    pub supplemental: SupplementalChoice,
+}
+
+impl Default for SessionOptions {
+    /// Everything from the config file, outside of a block
+    fn default() -> Self {
+        SessionOptions {
+            seventh_week: None,
+            block: None,
+            cycle: 1,
+            variant: None,
+            jokers: 0,
+            joker_increment: None,
+            scheme: None,
+            supplemental: SupplementalChoice::default(),
+        }
+    }
+}
+
+/// Optional parts of a session's workout
+#[derive(Debug, Clone, Default)]
+pub struct WorkoutOptions {
+    /// Whether to start with the general warm-up
+    pub warm_up: bool,
+    /// Whether to do the Limber 11 after the warm-up
+    pub mobility: bool,
+    /// Number of core exercises to pick at random
+    pub core_exercises: usize,
+    /// Reps done on the TM test set, to report whether to keep the training max
+    pub tm_test_reps: Option<i8>,
+    /// Formula and best e1RM so far for the primary lift, to note the reps to
+    /// beat on AMRAP sets
+    pub best_e1rm: Option<(E1rmFormula, f32)>,
+}
+
+impl Session {
+    /// Generates the sections of the session in the order they're done. Notes
+    /// that mention a weight are formatted with `units`.
+    pub fn workout(
+        &self,
+        options: &WorkoutOptions,
+        units: &Units,
+        rng: &mut impl Rng,
+    ) -> Result<Workout, WorkoutError> {
+        let Session {
+            primary_lift,
+            week,
+            position,
+            variant,
+            jokers,
+            supplemental,
+            scheme,
+            rounding,
+            training_maxes,
+            ..
+        } = self;
+        let mut sections = vec![];
+        let text = |s: &str| Exercise::Text(s.to_owned());
+
+        if let Some(position) = position {
+            let mut section = Section::new(&position.to_string());
+            section
+                .exercises
+                .push(Exercise::Text(format!("primary: {}", variant)));
+            if let Some(supplemental) = supplemental.filter(|_| !week.is_deload()) {
+                section
+                    .exercises
+                    .push(Exercise::Text(format!("supplemental: {}", supplemental)));
+            }
+            sections.push(section);
+        }
+
+        if options.warm_up {
+            let mut section = Section::new("Warm-up");
+            section.exercises.extend(WARM_UP.iter().map(|s| text(s)));
+            sections.push(section);
+        }
+
+        if options.mobility {
+            let mut section = Section::new("Limber 11");
+            section.exercises.extend(LIMBER_11.iter().map(|s| text(s)));
+            sections.push(section);
+        }
+
+        let mut section = Section::new("Primary lift");
+        let primary_sets = generate_primary_sets(
+            primary_lift,
+            week,
+            variant,
+            *jokers,
+            scheme,
+            rounding,
+            training_maxes,
+        )?;
+        for set_group in primary_sets {
+            let note = options
+                .best_e1rm
+                .filter(|_| set_group.amrap)
+                .map(
+                    |(formula, best)| match formula.reps_to_beat(set_group.weight, best) {
+                        Some(reps) => format!(
+                            "{} reps to beat best e1RM of {}",
+                            reps,
+                            units.format(best.round())
+                        ),
+                        None => format!(
+                            "best e1RM of {} is out of reach",
+                            units.format(best.round())
+                        ),
+                    },
+                );
+            section.exercises.push(Exercise::Sets { set_group, note });
+        }
+        sections.push(section);
+
+        if let Some(supplemental) = supplemental {
+            let supplemental_sets = generate_supplemental_sets(
+                primary_lift,
+                week,
+                supplemental,
+                scheme,
+                rounding,
+                training_maxes,
+            )?;
+            if !supplemental_sets.is_empty() {
+                let mut section = Section::new("Supplemental");
+                section
+                    .exercises
+                    .extend(supplemental_sets.into_iter().map(Exercise::from));
+                sections.push(section);
+            }
+        }
+
+        if let Some(reps) = options.tm_test_reps {
+            let mut section = Section::new("TM test");
+            let result = match evaluate_tm_test(primary_lift, reps, rounding, training_maxes)? {
+                TmTestResult::Fail { reset_to } => {
+                    format!("fail, reset training max to {}", units.format(reset_to))
+                }
+                result => result.to_string(),
+            };
+            section.exercises.push(Exercise::Text(format!(
+                "{} {} x{}: {}",
+                primary_lift,
+                units.format(training_maxes[primary_lift]),
+                reps,
+                result
+            )));
+            sections.push(section);
+        }
+
+        let mut section = Section::new("Assistance lifts");
+        section.exercises =
+            generate_assistance_sets(primary_lift, week, scheme, rounding, training_maxes, rng)?;
+        sections.push(section);
+
+        if options.core_exercises > 0 {
+            let mut section = Section::new("Core");
+            let core_exercises = CORE_EXERCISES.choose_multiple(rng, options.core_exercises);
+            section.exercises.extend(core_exercises.map(|s| text(s)));
+            sections.push(section);
+        }
+
+        Ok(Workout {
+            primary_lift: *primary_lift,
+            week: *week,
+            sections,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lifts::BbbPercent;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn session(week: Week) -> Session {
+        let mut training_maxes = HashMap::new();
+        training_maxes.insert(Lift::OverheadPress, 170.0);
+        training_maxes.insert(Lift::CloseGripBenchPress, 215.0);
+        Session {
+            primary_lift: Lift::OverheadPress,
+            week,
+            position: None,
+            phase_template: None,
+            variant: PrimaryVariant::Standard,
+            jokers: 0,
+            supplemental: None,
+            scheme: Scheme::default(),
+            rounding: RoundingRules::default(),
+            training_maxes,
+        }
+    }
+
+    #[test]
+    fn workout_has_sections_in_order_with_optional_parts() {
+        let options = WorkoutOptions {
+            warm_up: true,
+            core_exercises: 2,
+            best_e1rm: Some((E1rmFormula::Wendler, 200.0)),
+            ..Default::default()
+        };
+        let mut rng = StdRng::seed_from_u64(1);
+        let session_with_bbb = Session {
+            supplemental: Some(Supplemental::BoringButBig(BbbPercent::Fifty)),
+            ..session(Week::Week1)
+        };
+        let workout = session_with_bbb
+            .workout(&options, &Units::default(), &mut rng)
+            .unwrap();
+        let names: Vec<&str> = workout
+            .sections
+            .iter()
+            .map(|section| section.name.as_str())
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                "Warm-up",
+                "Primary lift",
+                "Supplemental",
+                "Assistance lifts",
+                "Core"
+            ]
+        );
+        assert_eq!(
+            workout.sections[1].exercises.last().unwrap().to_string(),
+            "overhead press 145 x5+ (12 reps to beat best e1RM of 200)"
+        );
+        assert_eq!(
+            workout.sections[2].exercises[0].to_string(),
+            "overhead press 85 5x10"
+        );
+        assert_eq!(workout.sections[4].exercises.len(), 2);
+
+        let options = WorkoutOptions {
+            tm_test_reps: Some(3),
+            ..Default::default()
+        };
+        let workout = session(Week::Week7(SeventhWeek::TmTest))
+            .workout(&options, &Units::default(), &mut rng)
+            .unwrap();
+        assert_eq!(
+            workout.sections[1].exercises,
+            vec![Exercise::Text(
+                "overhead press 170 x3: fail, reset training max to 153".to_owned()
+            )]
+        );
+    }
+}
@@ -1,8 +1,7 @@
+//! Fixed lists of exercises that aren't generated from training maxes.
+
 /// Warm-up routine never changes
-pub static WARM_UP: [&str; 2] = [
-    "5min jump rope, jog, row, or bike",
-    "2x15 box jumps",
-];
+pub static WARM_UP: [&str; 2] = ["5min jump rope, jog, row, or bike", "2x15 box jumps"];
 
 /// Limber 11, done after warm-up and before main exercises, never changes
 pub static LIMBER_11: [&str; 11] = [
@@ -0,0 +1,116 @@
+//! Plain text rendering of workouts, as printed to the terminal.
+
+use crate::lifts::SetGroup;
+use crate::plates::PlateInventory;
+use crate::units::Units;
+use crate::workout::{Exercise, Workout};
+
+/// Underlined section header, e.g. "Primary lift\n====================\n"
+pub fn header(text: &str) -> String {
+    format!("{}\n====================\n", text)
+}
+
+/// Blank lines after a section
+pub const SPACER: &str = "\n\n";
+
+/// Formats a set with its weight in the display unit(s).
+pub fn format_set_group(set_group: &SetGroup, units: &Units) -> String {
+    set_group.to_string_with_weight(&units.format(set_group.weight))
+}
+
+/// Formats a set with an optional note, followed by its plate breakdown when
+/// `plates` is given. Plates are always in the configured unit, which is named
+/// when weights are printed with units.
+fn format_set_group_line(
+    set_group: &SetGroup,
+    note: Option<&String>,
+    plates: Option<&PlateInventory>,
+    units: &Units,
+) -> String {
+    let mut line = format!("  {}", format_set_group(set_group, units));
+    if let Some(note) = note {
+        line += &format!(" ({})", note);
+    }
+    if let Some(plates) = plates {
+        match units.display {
+            Some(_) => line += &format!(" [{}: {}]", units.unit, set_group.plates(plates)),
+            None => line += &format!(" [{}]", set_group.plates(plates)),
+        }
+    }
+    line
+}
+
+/// Renders each section of a workout under its header, as printed to the
+/// terminal.
+pub fn render_workout(workout: &Workout, units: &Units, plates: Option<&PlateInventory>) -> String {
+    let mut s = String::new();
+    for section in workout.sections.iter() {
+        s += &header(&section.name);
+        for exercise in section.exercises.iter() {
+            match exercise {
+                Exercise::Sets { set_group, note } => {
+                    s += &format_set_group_line(set_group, note.as_ref(), plates, units)
+                }
+                Exercise::Text(text) => s += &format!("  {}", text),
+            }
+            s += "\n";
+        }
+        s += SPACER;
+    }
+    s
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lifts::{generate_primary_sets, Lift, PrimaryVariant, Week};
+    use crate::rounding::RoundingRules;
+    use crate::scheme::Scheme;
+    use crate::units::DisplayUnit;
+    use crate::workout::Section;
+    use std::collections::HashMap;
+
+    #[test]
+    fn renders_sections_with_units_notes_and_plates() {
+        let mut training_maxes = HashMap::new();
+        training_maxes.insert(Lift::Squat, 325.0);
+        let mut primary = Section::new("Primary lift");
+        let set_groups = generate_primary_sets(
+            &Lift::Squat,
+            &Week::Week3,
+            &PrimaryVariant::Standard,
+            0,
+            &Scheme::default(),
+            &RoundingRules::default(),
+            &training_maxes,
+        )
+        .unwrap();
+        let top_set = set_groups.last().unwrap().clone();
+        primary.exercises.push(Exercise::Sets {
+            set_group: top_set,
+            note: Some("3 reps to beat best e1RM of 340 lb".to_owned()),
+        });
+        let mut core = Section::new("Core");
+        core.exercises
+            .push(Exercise::Text("side plank, 3x10/side".to_owned()));
+        let workout = Workout {
+            primary_lift: Lift::Squat,
+            week: Week::Week3,
+            sections: vec![primary, core],
+        };
+
+        let units = Units {
+            display: Some(DisplayUnit::Pound),
+            ..Units::default()
+        };
+        assert_eq!(
+            render_workout(&workout, &units, Some(&PlateInventory::default())),
+            "Primary lift\n====================\n\
+             \x20 squat 309 lb x1+ (3 reps to beat best e1RM of 340 lb) [lb: 45 45 35 5 per side; not loadable, closest is 305]\n\
+             \n\n\
+             Core\n====================\n\
+             \x20 side plank, 3x10/side\n\
+             \n\n"
+        );
+    }
+}
@@ -0,0 +1,335 @@
+//! The TOML training log of completed sessions and their AMRAP reps.
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::Path;
+
+use crate::lifts::{Lift, SetGroup, SeventhWeek, Week, WorkoutError};
+use crate::units::Unit;
+
+/// A set as prescribed and, for AMRAP sets, as done
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LoggedSet {
+    /// Weight in the session's unit
+    pub weight: f32,
+    /// Number of sets
+    pub sets: i8,
+    /// Prescribed reps per set, or the least reps for a rep range
+    pub reps: i8,
+    /// Upper end of a rep range such as "x3-5"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_reps: Option<i8>,
+    /// Whether the last set is done for as many reps as possible
+    pub amrap: bool,
+    /// Reps completed on an AMRAP set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub actual_reps: Option<i8>,
+    /// The set's note, e.g. "joker, optional"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+}
+
+/// A completed session's primary lift work
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LoggedSession {
+    /// Day the session was done
+    pub date: NaiveDate,
+    /// Primary lift of the session
+    #[serde(with = "lift_name")]
+    pub lift: Lift,
+    /// Week number as written on the CLI
+    pub week: u8,
+    /// Protocol of a 7th week session; `None` for weeks 1-4 and for 7th weeks
+    /// logged before it was recorded
+    #[serde(
+        default,
+        with = "seventh_week_name",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub seventh_week: Option<SeventhWeek>,
+    /// Unit the set weights are in; sessions logged before units existed are in pounds
+    #[serde(default)]
+    pub unit: Unit,
+    /// Free-form notes about the session
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
+    /// Primary lift sets in the order they were done
+    pub sets: Vec<LoggedSet>,
+}
+
+impl LoggedSession {
+    /// Pairs the prescribed sets with the reps done on each AMRAP set, in order.
+    pub fn new(
+        date: NaiveDate,
+        lift: Lift,
+        week: Week,
+        unit: Unit,
+        set_groups: &[SetGroup],
+        amrap_reps: &[i8],
+        notes: Option<String>,
+    ) -> Result<LoggedSession, WorkoutError> {
+        let amrap_sets = set_groups
+            .iter()
+            .filter(|set_group| set_group.amrap)
+            .count();
+        if amrap_reps.len() != amrap_sets {
+            return Err(WorkoutError::Config(format!(
+                "Expected {} AMRAP result(s) for {} week {}, got {}",
+                amrap_sets,
+                lift,
+                week.number(),
+                amrap_reps.len()
+            )));
+        }
+        if let Some(reps) = amrap_reps.iter().find(|reps| **reps < 0) {
+            return Err(WorkoutError::Config(format!(
+                "AMRAP reps must not be negative, got {}",
+                reps
+            )));
+        }
+
+        let mut amrap_reps = amrap_reps.iter();
+        let sets = set_groups
+            .iter()
+            .map(|set_group| LoggedSet {
+                weight: set_group.weight,
+                sets: set_group.sets,
+                reps: set_group.reps,
+                max_reps: set_group.max_reps,
+                amrap: set_group.amrap,
+                actual_reps: if set_group.amrap {
+                    amrap_reps.next().copied()
+                } else {
+                    None
+                },
+                note: set_group.note.map(str::to_owned),
+            })
+            .collect();
+
+        Ok(LoggedSession {
+            date,
+            lift,
+            week: week.number(),
+            seventh_week: week.seventh_week(),
+            unit,
+            notes,
+            sets,
+        })
+    }
+
+    /// Whether the session was a 7th week TM test, whose sets at 100% of the
+    /// training max aren't PR attempts
+    pub fn is_tm_test(&self) -> bool {
+        self.seventh_week == Some(SeventhWeek::TmTest)
+    }
+}
+
+/// Lifts are stored by their config file key, e.g. "bench_press"
+mod lift_name {
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serializer};
+    use std::str::FromStr;
+
+    use crate::lifts::Lift;
+
+    pub fn serialize<S: Serializer>(lift: &Lift, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(lift.config_key())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Lift, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        Lift::from_str(&name).map_err(|_| D::Error::custom(format!("unknown lift '{}'", name)))
+    }
+}
+
+/// 7th week protocols are stored by their CLI name, e.g. "tm-test"
+mod seventh_week_name {
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serializer};
+    use std::str::FromStr;
+
+    use crate::lifts::SeventhWeek;
+
+    pub fn serialize<S: Serializer>(
+        protocol: &Option<SeventhWeek>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match protocol {
+            Some(protocol) => serializer.serialize_str(protocol.name()),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<SeventhWeek>, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        SeventhWeek::from_str(&name)
+            .map(Some)
+            .map_err(|_| D::Error::custom(format!("unknown 7th week protocol '{}'", name)))
+    }
+}
+
+/// Layout of the log file: one `[[session]]` table per logged session
+#[derive(Serialize, Deserialize, Default)]
+struct TrainingLog {
+    #[serde(default)]
+    session: Vec<LoggedSession>,
+}
+
+/// Parses the contents of a training log; `source` names it in error messages.
+pub fn parse_log_from_str(
+    contents: &str,
+    source: &str,
+) -> Result<Vec<LoggedSession>, WorkoutError> {
+    let log: TrainingLog = toml::from_str(contents).map_err(|err| {
+        WorkoutError::Config(format!("Unable to parse training log {}: {}", source, err))
+    })?;
+    Ok(log.session)
+}
+
+/// Reads every logged session, or none if the log file doesn't exist yet.
+pub fn read_log(path: &Path) -> Result<Vec<LoggedSession>, WorkoutError> {
+    let source = path.to_string_lossy().into_owned();
+    match std::fs::read_to_string(path) {
+        Ok(contents) => parse_log_from_str(&contents, &source),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(vec![]),
+        Err(err) => Err(WorkoutError::Config(format!(
+            "Unable to read {}: {}",
+            source, err
+        ))),
+    }
+}
+
+/// Appends a session to the log file, creating it if needed. Earlier sessions
+/// are left untouched.
+pub fn append_to_log(path: &Path, session: &LoggedSession) -> Result<(), WorkoutError> {
+    let source = path.to_string_lossy().into_owned();
+    let entry = toml::to_string(&TrainingLog {
+        session: vec![session.clone()],
+    })
+    .map_err(|err| {
+        WorkoutError::Config(format!("Unable to write session to {}: {}", source, err))
+    })?;
+
+    let write_err =
+        |err: std::io::Error| WorkoutError::Config(format!("Unable to write {}: {}", source, err));
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(write_err)?;
+    let separator = if file.metadata().map_err(write_err)?.len() > 0 {
+        "\n"
+    } else {
+        ""
+    };
+    write!(file, "{}{}", separator, entry).map_err(write_err)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lifts::{generate_primary_sets, PrimaryVariant};
+    use crate::rounding::RoundingRules;
+    use crate::scheme::Scheme;
+    use std::collections::HashMap;
+
+    fn squat_week_three_sets() -> Vec<SetGroup> {
+        let mut training_maxes = HashMap::new();
+        training_maxes.insert(Lift::Squat, 325.0);
+        generate_primary_sets(
+            &Lift::Squat,
+            &Week::Week3,
+            &PrimaryVariant::Standard,
+            1,
+            &Scheme::default(),
+            &RoundingRules::default(),
+            &training_maxes,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn logged_session_records_amrap_reps_on_amrap_sets() {
+        let date = NaiveDate::from_ymd_opt(2025, 3, 1).unwrap();
+        let session = LoggedSession::new(
+            date,
+            Lift::Squat,
+            Week::Week3,
+            Unit::Pound,
+            &squat_week_three_sets(),
+            &[4],
+            Some("felt fast".to_owned()),
+        )
+        .unwrap();
+        assert_eq!(session.sets.len(), 7);
+        assert_eq!(session.sets[5].weight, 309.0);
+        assert_eq!(session.sets[5].actual_reps, Some(4));
+        assert_eq!(session.sets[6].note.as_deref(), Some("joker, optional"));
+        assert!(session
+            .sets
+            .iter()
+            .filter(|set| !set.amrap)
+            .all(|set| set.actual_reps.is_none()));
+
+        let err = LoggedSession::new(
+            date,
+            Lift::Squat,
+            Week::Week3,
+            Unit::Pound,
+            &squat_week_three_sets(),
+            &[],
+            None,
+        )
+        .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Expected 1 AMRAP result(s) for squat week 3, got 0"));
+    }
+
+    #[test]
+    fn appended_sessions_read_back_in_order() {
+        let mut path = std::env::temp_dir();
+        path.push(format!("five-three-one-log-{}.toml", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        assert!(read_log(&path).unwrap().is_empty());
+        let first = LoggedSession::new(
+            NaiveDate::from_ymd_opt(2025, 3, 1).unwrap(),
+            Lift::Squat,
+            Week::Week3,
+            Unit::Pound,
+            &squat_week_three_sets(),
+            &[4],
+            None,
+        )
+        .unwrap();
+        let second = LoggedSession {
+            date: NaiveDate::from_ymd_opt(2025, 3, 3).unwrap(),
+            lift: Lift::BenchPress,
+            notes: Some("paused reps".to_owned()),
+            ..first.clone()
+        };
+        let third = LoggedSession {
+            week: 7,
+            seventh_week: Some(SeventhWeek::TmTest),
+            ..first.clone()
+        };
+        append_to_log(&path, &first).unwrap();
+        append_to_log(&path, &second).unwrap();
+        append_to_log(&path, &third).unwrap();
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert!(contents.contains("lift = \"bench_press\""));
+        assert!(contents.contains("unit = \"lb\""));
+        assert_eq!(contents.matches("seventh_week = \"tm-test\"").count(), 1);
+        assert_eq!(
+            parse_log_from_str(&contents, "log.toml").unwrap(),
+            vec![first, second, third]
+        );
+    }
+}
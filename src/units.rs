@@ -0,0 +1,141 @@
+//! Pounds and kilograms, and how weights are printed in them.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use strum_macros::EnumString;
+
+/// Pounds in a kilogram
+const POUNDS_PER_KILOGRAM: f32 = 2.204_622_6;
+
+/// Unit training maxes, plates and rounding increments are written in
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, EnumString, Serialize, Deserialize)]
+pub enum Unit {
+    /// Pounds, the default
+    #[default]
+    #[strum(
+        serialize = "lb",
+        serialize = "lbs",
+        serialize = "pound",
+        serialize = "pounds"
+    )]
+    #[serde(rename = "lb")]
+    Pound,
+    /// Kilograms
+    #[strum(
+        serialize = "kg",
+        serialize = "kgs",
+        serialize = "kilogram",
+        serialize = "kilograms"
+    )]
+    #[serde(rename = "kg")]
+    Kilogram,
+}
+
+impl fmt::Display for Unit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s: &str = match self {
+            Unit::Pound => "lb",
+            Unit::Kilogram => "kg",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+impl Unit {
+    /// Converts `weight` from this unit to `to`.
+    pub fn convert(&self, weight: f32, to: Unit) -> f32 {
+        match (self, to) {
+            (Unit::Pound, Unit::Kilogram) => weight / POUNDS_PER_KILOGRAM,
+            (Unit::Kilogram, Unit::Pound) => weight * POUNDS_PER_KILOGRAM,
+            _ => weight,
+        }
+    }
+}
+
+/// Unit(s) weights are printed in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumString)]
+pub enum DisplayUnit {
+    /// Pounds
+    #[strum(
+        serialize = "lb",
+        serialize = "lbs",
+        serialize = "pound",
+        serialize = "pounds"
+    )]
+    Pound,
+    /// Kilograms
+    #[strum(
+        serialize = "kg",
+        serialize = "kgs",
+        serialize = "kilogram",
+        serialize = "kilograms"
+    )]
+    Kilogram,
+    /// The configured unit first, then the other one
+    #[strum(serialize = "both")]
+    Both,
+}
+
+/// Unit weights are configured in and how they're printed
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Units {
+    /// Unit of training maxes and every weight computed from them
+    pub unit: Unit,
+    /// Without a display unit, weights are printed as bare numbers in `unit`
+    pub display: Option<DisplayUnit>,
+}
+
+impl Units {
+    /// Formats a weight given in the configured unit for display, e.g. "140",
+    /// "309 lb" or "140 kg / 309 lb". Converted weights are shown to the
+    /// nearest tenth.
+    pub fn format(&self, weight: f32) -> String {
+        let other = match self.unit {
+            Unit::Pound => Unit::Kilogram,
+            Unit::Kilogram => Unit::Pound,
+        };
+        let in_unit = |unit: Unit| {
+            let converted = (self.unit.convert(weight, unit) * 10.0).round() / 10.0;
+            format!("{} {}", converted, unit)
+        };
+        match self.display {
+            None => weight.to_string(),
+            Some(DisplayUnit::Pound) => in_unit(Unit::Pound),
+            Some(DisplayUnit::Kilogram) => in_unit(Unit::Kilogram),
+            Some(DisplayUnit::Both) => format!("{} / {}", in_unit(self.unit), in_unit(other)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_between_pounds_and_kilograms() {
+        assert_eq!(Unit::Kilogram.convert(100.0, Unit::Pound).round(), 220.0);
+        assert_eq!(Unit::Pound.convert(225.0, Unit::Kilogram).round(), 102.0);
+        assert_eq!(Unit::Kilogram.convert(140.0, Unit::Kilogram), 140.0);
+    }
+
+    #[test]
+    fn formats_weights_in_display_units() {
+        let kg = |display| Units {
+            unit: Unit::Kilogram,
+            display,
+        };
+        assert_eq!(kg(None).format(140.0), "140");
+        assert_eq!(kg(Some(DisplayUnit::Kilogram)).format(140.0), "140 kg");
+        assert_eq!(kg(Some(DisplayUnit::Pound)).format(140.0), "308.6 lb");
+        assert_eq!(
+            kg(Some(DisplayUnit::Both)).format(140.0),
+            "140 kg / 308.6 lb"
+        );
+
+        let lb = Units {
+            unit: Unit::Pound,
+            display: Some(DisplayUnit::Both),
+        };
+        assert_eq!(lb.format(315.0), "315 lb / 142.9 kg");
+    }
+}
@@ -0,0 +1,146 @@
+//! Warm-up ramps done before a primary lift's first working set.
+
+use std::collections::HashMap;
+
+use crate::lifts::Lift;
+use crate::plates::PlateInventory;
+use crate::scheme::SetScheme;
+use crate::units::Unit;
+
+/// Keeps a warm-up set exactly `min_gap` below the first working set from
+/// being skipped by floating point error
+const TOLERANCE: f32 = 0.0001;
+
+/// Reps of the empty bar set when the config file asks for one without a count
+pub const DEFAULT_EMPTY_BAR_REPS: i8 = 10;
+
+/// Warm-up ramp done before a primary lift's first working set
+#[derive(Debug, Clone, PartialEq)]
+pub struct WarmUp {
+    /// Warm-up sets as fractions of training max, lightest first
+    pub sets: Vec<SetScheme>,
+    /// Reps with the empty bar before the first warm-up set, or `None` to
+    /// start at the first warm-up set
+    pub empty_bar_reps: Option<i8>,
+    /// Weight of the empty bar
+    pub bar: f32,
+    /// Warm-up sets that are less than this fraction of training max lighter
+    /// than the first working set are skipped
+    pub min_gap: f32,
+}
+
+impl Default for WarmUp {
+    /// The default ramp with a 45 lb bar
+    fn default() -> Self {
+        WarmUp::for_unit(Unit::Pound)
+    }
+}
+
+impl WarmUp {
+    /// 40x5, 50x5 and 60x3, skipping sets within 10% of training max of the
+    /// first working set: the 60% set in the 5s week (first working set at
+    /// 65%), and every set in the deload week (40%). The empty bar is the
+    /// default `[plates]` bar for `unit`, 45 lb or 20 kg.
+    pub fn for_unit(unit: Unit) -> WarmUp {
+        let set = SetScheme::new;
+        WarmUp {
+            sets: vec![set(0.4, 5, false), set(0.5, 5, false), set(0.6, 3, false)],
+            empty_bar_reps: None,
+            bar: PlateInventory::for_unit(unit).bar,
+            min_gap: 0.1,
+        }
+    }
+
+    /// Warm-up sets to do before a first working set at `first_working` of
+    /// training max.
+    pub fn sets_before(&self, first_working: f32) -> Vec<SetScheme> {
+        self.sets
+            .iter()
+            .filter(|set| first_working - set.scalar + TOLERANCE >= self.min_gap)
+            .copied()
+            .collect()
+    }
+
+    /// Whether to start with the empty bar before a first working set of
+    /// `first_working_weight`. Like the other warm-up sets, it's skipped when
+    /// it's too close to the first working set.
+    pub fn empty_bar_before(&self, first_working_weight: f32, training_max: f32) -> Option<i8> {
+        self.empty_bar_reps
+            .filter(|_| first_working_weight - self.bar + TOLERANCE >= self.min_gap * training_max)
+    }
+}
+
+/// Warm-up ramps for every lift, with per-lift overrides
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WarmUpRules {
+    /// Warm-up for lifts without their own
+    pub default: WarmUp,
+    /// Per-lift warm-ups, overriding `default`
+    pub lifts: HashMap<Lift, WarmUp>,
+}
+
+impl WarmUpRules {
+    /// The unit's default warm-up for every lift
+    pub fn for_unit(unit: Unit) -> WarmUpRules {
+        WarmUpRules {
+            default: WarmUp::for_unit(unit),
+            lifts: HashMap::new(),
+        }
+    }
+
+    /// Warm-up for `lift`: its own, or the default.
+    pub fn for_lift(&self, lift: &Lift) -> &WarmUp {
+        self.lifts.get(lift).unwrap_or(&self.default)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_ramp_skips_sets_close_to_first_working_set() {
+        let warm_up = WarmUp::default();
+        let percents = |first_working| -> Vec<f32> {
+            warm_up
+                .sets_before(first_working)
+                .iter()
+                .map(|set| set.scalar)
+                .collect()
+        };
+        assert_eq!(percents(0.65), vec![0.4, 0.5]);
+        assert_eq!(percents(0.7), vec![0.4, 0.5, 0.6]);
+        assert_eq!(percents(0.75), vec![0.4, 0.5, 0.6]);
+        assert!(percents(0.4).is_empty());
+    }
+
+    #[test]
+    fn empty_bar_is_skipped_when_too_close() {
+        let warm_up = WarmUp {
+            empty_bar_reps: Some(10),
+            ..WarmUp::default()
+        };
+        assert_eq!(warm_up.empty_bar_before(211.0, 325.0), Some(10));
+        // within 10% of a 170 training max
+        assert_eq!(warm_up.empty_bar_before(60.0, 170.0), None);
+        assert_eq!(WarmUp::default().empty_bar_before(211.0, 325.0), None);
+    }
+
+    #[test]
+    fn empty_bar_weighs_the_default_bar_for_the_unit() {
+        assert_eq!(WarmUp::default().bar, 45.0);
+        let warm_up = WarmUp {
+            empty_bar_reps: Some(10),
+            ..WarmUp::for_unit(Unit::Kilogram)
+        };
+        assert_eq!(warm_up.bar, 20.0);
+        // 28 kg is more than 10% of a 75 kg training max above the 20 kg bar
+        assert_eq!(warm_up.empty_bar_before(28.0, 75.0), Some(10));
+        assert_eq!(
+            WarmUpRules::for_unit(Unit::Kilogram)
+                .for_lift(&Lift::Squat)
+                .bar,
+            20.0
+        );
+    }
+}
@@ -0,0 +1,86 @@
+//! Workouts as named sections of sets and other exercises, ready to render.
+
+use std::fmt;
+
+use crate::lifts::{Lift, SetGroup, Week};
+
+/// An item of a workout section
+#[derive(Debug, Clone, PartialEq)]
+pub enum Exercise {
+    /// Sets with a prescribed weight, with an optional note such as the reps to
+    /// beat the best e1RM
+    Sets {
+        /// The sets to do
+        set_group: SetGroup,
+        /// Shown in parentheses after the sets
+        note: Option<String>,
+    },
+    /// An exercise or instruction without a prescribed weight, e.g. "chin-ups, 2x10"
+    Text(String),
+}
+
+impl From<SetGroup> for Exercise {
+    fn from(set_group: SetGroup) -> Exercise {
+        Exercise::Sets {
+            set_group,
+            note: None,
+        }
+    }
+}
+
+impl fmt::Display for Exercise {
+    /// e.g. "squat 276 x5+ (7 reps to beat best e1RM of 340)" or "chin-ups, 2x10"
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Exercise::Sets {
+                set_group,
+                note: None,
+            } => write!(f, "{}", set_group),
+            Exercise::Sets {
+                set_group,
+                note: Some(note),
+            } => write!(f, "{} ({})", set_group, note),
+            Exercise::Text(text) => write!(f, "{}", text),
+        }
+    }
+}
+
+/// A titled part of a workout, e.g. "Primary lift"
+#[derive(Debug, Clone, PartialEq)]
+pub struct Section {
+    /// Title printed above the section
+    pub name: String,
+    /// What's done in the section, in order
+    pub exercises: Vec<Exercise>,
+}
+
+impl Section {
+    /// An empty section titled `name`
+    pub fn new(name: &str) -> Section {
+        Section {
+            name: name.to_owned(),
+            exercises: vec![],
+        }
+    }
+}
+
+/// Everything done in one primary lift's session, section by section
+#[derive(Debug, Clone, PartialEq)]
+pub struct Workout {
+    /// Lift the session is built around
+    pub primary_lift: Lift,
+    /// Week of the cycle
+    pub week: Week,
+    /// Sections in the order they're done
+    pub sections: Vec<Section>,
+}
+
+impl Workout {
+    /// e.g. "squat week 1" or "deadlift week 7 (TM test)"
+    pub fn title(&self) -> String {
+        match self.week {
+            Week::Week7(protocol) => format!("{} week 7 ({})", self.primary_lift, protocol),
+            week => format!("{} week {}", self.primary_lift, week.number()),
+        }
+    }
+}
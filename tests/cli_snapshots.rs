@@ -1,8 +1,8 @@
-use std::path::PathBuf;
-use std::process::Command;
 use std::env;
 use std::fs;
 use std::path::Path;
+use std::path::PathBuf;
+use std::process::Command;
 
 fn project_root() -> PathBuf {
     PathBuf::from(env!("CARGO_MANIFEST_DIR"))
@@ -15,11 +15,27 @@ fn config_path() -> String {
         .into_owned()
 }
 
+/// A training log path that doesn't exist, so a local `training_log.toml`
+/// can't add rep targets to the snapshots
+fn missing_log_path() -> String {
+    env::temp_dir()
+        .join(format!("five-three-one-no-log-{}.toml", std::process::id()))
+        .to_string_lossy()
+        .into_owned()
+}
+
 fn run_cli_with_seed(args: &[&str]) -> String {
+    run_cli_with_log(args, &missing_log_path())
+}
+
+fn run_cli_with_log(args: &[&str], log_path: &str) -> String {
     let exe = env::var("CARGO_BIN_EXE_five_three_one")
         .map(PathBuf::from)
         .unwrap_or_else(|_| {
-            let debug_exe = Path::new(&project_root()).join("target").join("debug").join("five-three-one");
+            let debug_exe = Path::new(&project_root())
+                .join("target")
+                .join("debug")
+                .join("five-three-one");
             let release_exe = Path::new(&project_root())
                 .join("target")
                 .join("release")
@@ -36,10 +52,15 @@ fn run_cli_with_seed(args: &[&str]) -> String {
 
     let output = Command::new(exe)
         .args(args)
+        .args(["--log", log_path])
         .output()
         .expect("failed to execute five-three-one binary");
 
-    assert!(output.status.success(), "command failed with status {:?}", output.status);
+    assert!(
+        output.status.success(),
+        "command failed with status {:?}",
+        output.status
+    );
     let stdout = String::from_utf8(output.stdout).expect("stdout should be valid UTF-8");
     stdout.replace("\r\n", "\n")
 }
@@ -60,6 +81,26 @@ fn snapshot_squat_week_one_seed123() {
     assert_eq!(output, expected);
 }
 
+#[test]
+fn snapshot_squat_week_one_rep_target_from_log_seed123() {
+    let log_path = project_root().join("tests/fixtures/training_log.toml");
+    let output = run_cli_with_log(
+        &[
+            "--primary-lift",
+            "squat",
+            "--week",
+            "1",
+            "--seed",
+            "123",
+            "--config",
+            &config_path(),
+        ],
+        &log_path.to_string_lossy(),
+    );
+    let expected = include_str!("fixtures/squat-week1-logged-seed123.txt");
+    assert_eq!(output, expected);
+}
+
 #[test]
 fn snapshot_deadlift_week_two_with_warmup_mobility_core_seed321() {
     let output = run_cli_with_seed(&[
@@ -95,3 +136,212 @@ fn snapshot_overhead_press_week_four_seed7() {
     let expected = include_str!("fixtures/overhead-press-week4-seed7.txt");
     assert_eq!(output, expected);
 }
+
+#[test]
+fn snapshot_squat_week_three_bbb_ascending_seed5() {
+    let output = run_cli_with_seed(&[
+        "--primary-lift",
+        "squat",
+        "--week",
+        "3",
+        "--supplemental",
+        "bbb",
+        "--bbb-percent",
+        "ascending",
+        "--seed",
+        "5",
+        "--config",
+        &config_path(),
+    ]);
+    let expected = include_str!("fixtures/squat-week3-bbb-ascending-seed5.txt");
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn snapshot_overhead_press_week_two_pyramid_seed11() {
+    let output = run_cli_with_seed(&[
+        "--primary-lift",
+        "overhead-press",
+        "--week",
+        "2",
+        "--variant",
+        "pyramid",
+        "--seed",
+        "11",
+        "--config",
+        &config_path(),
+    ]);
+    let expected = include_str!("fixtures/overhead-press-week2-pyramid-seed11.txt");
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn snapshot_squat_leader_cycle_two_week_two_seed3() {
+    let output = run_cli_with_seed(&[
+        "--primary-lift",
+        "squat",
+        "--week",
+        "2",
+        "--block",
+        "leader",
+        "--cycle",
+        "2",
+        "--seed",
+        "3",
+        "--config",
+        &config_path(),
+    ]);
+    let expected = include_str!("fixtures/squat-leader-cycle2-week2-seed3.txt");
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn snapshot_deadlift_seventh_week_tm_test_seed9() {
+    let output = run_cli_with_seed(&[
+        "--primary-lift",
+        "deadlift",
+        "--week",
+        "7",
+        "--seventh-week",
+        "tm-test",
+        "--tm-test-reps",
+        "6",
+        "--seed",
+        "9",
+        "--config",
+        &config_path(),
+    ]);
+    let expected = include_str!("fixtures/deadlift-week7-tm-test-seed9.txt");
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn snapshot_bench_press_week_three_fsl_plates_seed4() {
+    let output = run_cli_with_seed(&[
+        "--primary-lift",
+        "bench-press",
+        "--week",
+        "3",
+        "--plates",
+        "--supplemental",
+        "fsl",
+        "--seed",
+        "4",
+        "--config",
+        &config_path(),
+    ]);
+    let expected = include_str!("fixtures/bench-press-week3-fsl-plates-seed4.txt");
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn snapshot_cycle_fsl_seed3() {
+    let output = run_cli_with_seed(&[
+        "cycle",
+        "--supplemental",
+        "fsl",
+        "--seed",
+        "3",
+        "--config",
+        &config_path(),
+    ]);
+    let expected = include_str!("fixtures/cycle-fsl-seed3.txt");
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn snapshot_bench_press_week_three_json_plates_seed4() {
+    let output = run_cli_with_seed(&[
+        "--primary-lift",
+        "bench-press",
+        "--week",
+        "3",
+        "--supplemental",
+        "fsl",
+        "--plates",
+        "--format",
+        "json",
+        "--seed",
+        "4",
+        "--config",
+        &config_path(),
+    ]);
+    let expected = include_str!("fixtures/bench-press-week3-fsl-plates-seed4.json");
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn snapshot_squat_week_one_markdown_bbb_warmup_plates_seed123() {
+    let output = run_cli_with_seed(&[
+        "--primary-lift",
+        "squat",
+        "--week",
+        "1",
+        "--warmup",
+        "--supplemental",
+        "bbb",
+        "--plates",
+        "--format",
+        "markdown",
+        "--seed",
+        "123",
+        "--config",
+        &config_path(),
+    ]);
+    let expected = include_str!("fixtures/squat-week1-bbb-warmup-plates-seed123.md");
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn snapshot_cycle_csv_bbb_seed3() {
+    let output = run_cli_with_seed(&[
+        "cycle",
+        "--supplemental",
+        "bbb",
+        "--format",
+        "csv",
+        "--seed",
+        "3",
+        "--config",
+        &config_path(),
+    ]);
+    let expected = include_str!("fixtures/cycle-bbb-seed3.csv");
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn snapshot_cycle_html_fsl_seed3() {
+    let output = run_cli_with_seed(&[
+        "cycle",
+        "--supplemental",
+        "fsl",
+        "--format",
+        "html",
+        "--seed",
+        "3",
+        "--config",
+        &config_path(),
+    ]);
+    let expected = include_str!("fixtures/cycle-fsl-seed3.html");
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn snapshot_overhead_press_week_three_csv_dated() {
+    let output = run_cli_with_seed(&[
+        "--primary-lift",
+        "overhead-press",
+        "--week",
+        "3",
+        "--format",
+        "csv",
+        "--date",
+        "2025-03-01",
+        "--seed",
+        "7",
+        "--config",
+        &config_path(),
+    ]);
+    let expected = include_str!("fixtures/overhead-press-week3-csv-2025-03-01.csv");
+    assert_eq!(output, expected);
+}